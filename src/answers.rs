@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use toml::{Table, Value};
+
+use crate::input::{self, InputSource};
+use crate::solution::Answers;
+
+//Known answers of every input, per year, day and variant (None for the real input)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    answers: BTreeMap<(u16, u8, Option<String>), Answers>,
+}
+
+//input/<year>/answers.toml
+pub fn manifest_path(year: u16) -> PathBuf {
+    input::year_dir(year).join("answers.toml")
+}
+
+impl Manifest {
+    //Answers of a single year, from its manifest at `path`
+    pub fn load(year: u16, path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+        Self::from_toml(year, &text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))
+    }
+
+    //Tables such as [day05.input] or [day05.test], each with a part1 and/or part2 answer
+    pub fn from_toml(year: u16, text: &str) -> Result<Self, String> {
+        let days = text.parse::<Table>().map_err(|e| e.to_string())?;
+        let mut answers = BTreeMap::new();
+        for (key, variants) in days.iter() {
+            let day = key.strip_prefix("day").and_then(|d| d.parse::<u8>().ok())
+                .ok_or_else(|| format!("[{}] is not a day such as [day05]", key))?;
+            let variants = variants.as_table().ok_or_else(|| format!("[{}] should hold a table per input", key))?;
+            for (variant, parts) in variants.iter() {
+                let part = |name: &str| match parts.get(name) {
+                    None => Ok(None),
+                    Some(Value::String(answer)) => Ok(Some(answer.clone())),
+                    Some(Value::Integer(answer)) => Ok(Some(answer.to_string())),
+                    Some(_) => Err(format!("{}.{}.{} should be a string or an integer", key, variant, name)),
+                };
+                let expected = Answers { part_1: part("part1")?, part_2: part("part2")? };
+                answers.insert((year, day, (variant != "input").then(|| variant.clone())), expected);
+            }
+        }
+        Ok(Manifest { answers })
+    }
+
+    pub fn get(&self, year: u16, day: u8, variant: Option<&str>) -> Option<&Answers> {
+        self.answers.get(&(year, day, variant.map(str::to_string)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u16, u8, Option<&str>, &Answers)> {
+        self.answers.iter().map(|((year, day, variant), answers)| (*year, *day, variant.as_deref(), answers))
+    }
+
+    //Every input of a day that has known answers
+    pub fn inputs(&self, year: u16, day: u8) -> impl Iterator<Item = (InputSource, &Answers)> {
+        self.iter().filter(move |(y, d, _, _)| (*y, *d) == (year, day)).map(|(_, _, variant, answers)| {
+            let source = match variant {
+                Some(variant) => InputSource::Variant(variant.to_string()),
+                None => InputSource::Default,
+            };
+            (source, answers)
+        })
+    }
+}
+
+//Whether `actual` agrees with every part `expected` has an answer for
+pub fn matches(expected: &Answers, actual: &Answers) -> bool {
+    expected.part_1.iter().all(|e| actual.part_1.as_ref() == Some(e))
+        && expected.part_2.iter().all(|e| actual.part_2.as_ref() == Some(e))
+}
+
+//(part, expected, actual) of every solved part that differs from its known answer
+pub fn mismatches<'a>(expected: &'a Answers, actual: &'a Answers) -> Vec<(u8, &'a str, &'a str)> {
+    [(1, &expected.part_1, &actual.part_1), (2, &expected.part_2, &actual.part_2)].into_iter()
+        .filter_map(|(part, expected, actual)| match (expected, actual) {
+            (Some(expected), Some(actual)) if expected != actual => Some((part, expected.as_str(), actual.as_str())),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::input;
+    use crate::registry;
+    use super::{manifest_path, matches, Manifest};
+
+    //Inputs in the year directory that are not puzzle inputs of their own
+    const NOT_PUZZLE_INPUTS: [(u16, &str); 1] = [(2022, "day17_rock.txt")];
+
+    fn check_day(year: u16, day: u8) {
+        let manifest = Manifest::load(year, &manifest_path(year)).unwrap();
+        let solver = registry::get(year, day).unwrap();
+        let mut checked = 0;
+        for (source, expected) in manifest.inputs(year, day) {
+            let text = input::load(year, day, &source).unwrap();
+            let (actual, _) = (solver.solve)(&text, None).unwrap_or_else(|e| panic!("{} day {} {:?}: {}", year, day, source, e));
+            assert!(matches(expected, &actual), "{} day {} {:?}: expected {:?}, got {:?}", year, day, source, expected, actual);
+            checked += 1;
+        }
+        assert!(checked > 0, "{} day {} has no answers in the manifest", year, day);
+    }
+
+    macro_rules! golden {
+        ($year:expr => $($name:ident: $day:expr),* $(,)?) => {
+            $(#[test] fn $name() { check_day($year, $day) })*
+        };
+    }
+
+    golden!(2022 =>
+        y2022_day01: 1, y2022_day02: 2, y2022_day03: 3, y2022_day04: 4, y2022_day05: 5,
+        y2022_day06: 6, y2022_day07: 7, y2022_day08: 8, y2022_day09: 9, y2022_day10: 10,
+        y2022_day11: 11, y2022_day12: 12, y2022_day13: 13, y2022_day14: 14, y2022_day15: 15,
+        y2022_day16: 16, y2022_day17: 17, y2022_day18: 18, y2022_day19: 19, y2022_day20: 20,
+        y2022_day21: 21, y2022_day22: 22, y2022_day23: 23, y2022_day24: 24, y2022_day25: 25,
+    );
+
+    #[test]
+    fn every_input_has_answers() {
+        for year in registry::years() {
+            check_year_coverage(year);
+        }
+    }
+
+    fn check_year_coverage(year: u16) {
+        let manifest = Manifest::load(year, &manifest_path(year)).unwrap();
+        for entry in fs::read_dir(input::year_dir(year)).unwrap() {
+            let file = entry.unwrap().file_name().into_string().unwrap();
+            let Some(name) = file.strip_prefix("day").and_then(|f| f.strip_suffix(".txt")) else { continue };
+            if NOT_PUZZLE_INPUTS.contains(&(year, file.as_str())) {
+                continue;
+            }
+            let (day, variant) = match name.split_once('_') {
+                Some((day, variant)) => (day, Some(variant)),
+                None => (name, None),
+            };
+            assert!(manifest.get(year, day.parse().unwrap(), variant).is_some(), "{}/{} has no answers in the manifest", year, file);
+        }
+    }
+
+    #[test]
+    fn manifest_accepts_strings_and_integers() {
+        let manifest = Manifest::from_toml(2022, "[day05.test]\npart1 = \"CMZ\"\n\n[day06.input]\npart2 = 19\n").unwrap();
+        assert_eq!(manifest.get(2022, 5, Some("test")).unwrap().part_1.as_deref(), Some("CMZ"));
+        assert_eq!(manifest.get(2022, 6, None).unwrap().part_2.as_deref(), Some("19"));
+        assert_eq!(manifest.get(2021, 6, None), None);
+        assert!(Manifest::from_toml(2022, "[five.test]\npart1 = 1\n").is_err());
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::hint::black_box;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Map, Value};
+
+use crate::{ParseError, Solution};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub iterations: u32,
+    pub warmup: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 3] = [Phase::Parse, Phase::Part1, Phase::Part2];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::Part1 => "part1",
+            Phase::Part2 => "part2",
+        }
+    }
+}
+
+//Summary of the samples taken of a single phase
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        assert!(!samples.is_empty(), "no samples to summarize");
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let mid = sorted.len() / 2;
+        let median = match sorted.len() % 2 {
+            0 => (sorted[mid - 1] + sorted[mid]) / 2,
+            _ => sorted[mid],
+        };
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / sorted.len() as f64;
+        let variance = sorted.iter().map(|d| (d.as_secs_f64() - mean).powi(2)).sum::<f64>() / sorted.len() as f64;
+        Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timings {
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+impl Timings {
+    pub fn get(&self, phase: Phase) -> &Stats {
+        match phase {
+            Phase::Parse => &self.parse,
+            Phase::Part1 => &self.part1,
+            Phase::Part2 => &self.part2,
+        }
+    }
+}
+
+//Times parsing and both parts separately
+pub fn time_phases<S: Solution>(input: &str, config: &Config) -> Result<Timings, ParseError> {
+    S::parse(input)?;
+    //Every sample of a part gets an input of its own, parsed untimed, so that work a solver shares between the parts
+    //is not cached across samples
+    let parsed = || S::parse(input).unwrap();
+    Ok(Timings {
+        parse: measure(config, || (), |_| S::parse(input)),
+        part1: measure(config, parsed, S::part1),
+        part2: measure(config, parsed, S::part2),
+    })
+}
+
+fn measure<I, T>(config: &Config, mut setup: impl FnMut() -> I, mut f: impl FnMut(&I) -> T) -> Stats {
+    for _ in 0..config.warmup {
+        black_box(f(&setup()));
+    }
+    let samples = (0..config.iterations).map(|_| {
+        let input = setup();
+        let start = Instant::now();
+        let result = black_box(f(&input));
+        let elapsed = start.elapsed();
+        drop((result, input));
+        elapsed
+    }).collect::<Vec<Duration>>();
+    Stats::from_samples(&samples)
+}
+
+//Slope of log(time) against log(size), fitted by least squares: about 1 for a linear solver and 2 for a quadratic one
+pub fn growth_exponent(points: &[(usize, Duration)]) -> Option<f64> {
+    let logs = points.iter()
+        .filter(|(size, time)| *size > 0 && !time.is_zero())
+        .map(|(size, time)| ((*size as f64).ln(), time.as_secs_f64().ln()))
+        .collect::<Vec<(f64, f64)>>();
+    let n = logs.len() as f64;
+    let (mean_x, mean_y) = (logs.iter().map(|p| p.0).sum::<f64>() / n, logs.iter().map(|p| p.1).sum::<f64>() / n);
+    let var_x = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum::<f64>();
+    let cov = logs.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum::<f64>();
+    (var_x > 0.0).then(|| cov / var_x)
+}
+
+//Median of every phase of every day, saved by an earlier run to compare against
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Baseline {
+    medians: BTreeMap<(u16, u8, Phase), Duration>,
+}
+
+impl Baseline {
+    //Loads a saved baseline, or an empty one if there is none yet
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Baseline::default()),
+            Err(e) => return Err(e),
+        };
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, format!("{}: not a benchmark baseline", path.display()));
+        let years = serde_json::from_str::<Value>(&text).map_err(|_| invalid())?;
+        let mut medians = BTreeMap::new();
+        for (year, days) in years.as_object().ok_or_else(invalid)? {
+            let year = year.parse::<u16>().map_err(|_| invalid())?;
+            for (day, phases) in days.as_object().ok_or_else(invalid)? {
+                let day = day.parse::<u8>().map_err(|_| invalid())?;
+                for phase in Phase::ALL {
+                    if let Some(nanos) = phases.get(phase.name()) {
+                        let nanos = nanos.as_u64().ok_or_else(invalid)?;
+                        medians.insert((year, day, phase), Duration::from_nanos(nanos));
+                    }
+                }
+            }
+        }
+        Ok(Baseline { medians })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut years = Map::new();
+        for ((year, day, phase), median) in self.medians.iter() {
+            let days = years.entry(year.to_string()).or_insert_with(|| json!({}));
+            let phases = days.as_object_mut().unwrap().entry(format!("{:02}", day)).or_insert_with(|| json!({}));
+            phases[phase.name()] = json!(median.as_nanos() as u64);
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&Value::Object(years))? + "\n")
+    }
+
+    pub fn get(&self, year: u16, day: u8, phase: Phase) -> Option<Duration> {
+        self.medians.get(&(year, day, phase)).copied()
+    }
+
+    pub fn update(&mut self, year: u16, day: u8, timings: &Timings) {
+        for phase in Phase::ALL {
+            self.medians.insert((year, day, phase), timings.get(phase).median);
+        }
+    }
+}
+
+//Relative slowdown of `current` over `baseline`, if it is more than `threshold` (0.1 = 10%). A baseline too fast to
+//measure has nothing to be relative to
+pub fn regression(baseline: Duration, current: Duration, threshold: f64) -> Option<f64> {
+    if baseline.is_zero() {
+        return None;
+    }
+    let change = current.as_secs_f64() / baseline.as_secs_f64() - 1.0;
+    (change > threshold).then_some(change)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{growth_exponent, regression, Stats};
+
+    #[test]
+    fn stats_summarize_samples() {
+        let samples = [4, 1, 3, 2].map(Duration::from_millis);
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert!((stats.stddev.as_secs_f64() - 1.25e-6_f64.sqrt()).abs() < 1e-9);
+
+        assert_eq!(regression(Duration::from_millis(10), Duration::from_millis(11), 0.2), None);
+        assert!(regression(Duration::from_millis(10), Duration::from_millis(13), 0.2).is_some());
+        assert_eq!(regression(Duration::ZERO, Duration::from_millis(1), 0.2), None);
+    }
+
+    #[test]
+    fn growth_exponent_of_curves() {
+        let linear = [10, 100, 1000].map(|n| (n, Duration::from_micros(3 * n as u64)));
+        let quadratic = [10, 100, 1000].map(|n| (n, Duration::from_nanos(5 * (n * n) as u64)));
+        assert!((growth_exponent(&linear).unwrap() - 1.0).abs() < 1e-9);
+        assert!((growth_exponent(&quadratic).unwrap() - 2.0).abs() < 1e-9);
+        assert_eq!(growth_exponent(&[(10, Duration::from_millis(1))]), None);
+    }
+}
@@ -0,0 +1,131 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::input::input_hash;
+use crate::registry::Day;
+use crate::solution::Answers;
+
+//Answers solved before, one file per day and input, so that an unchanged input is not solved again. Answers only count
+//for the version of the solver that computed them
+pub struct AnswerCache {
+    dir: PathBuf,
+}
+
+impl AnswerCache {
+    pub fn new(dir: PathBuf) -> Self {
+        AnswerCache { dir }
+    }
+
+    //target/answer_cache
+    pub fn default_dir() -> PathBuf {
+        PathBuf::from("target").join("answer_cache")
+    }
+
+    //Answers of the requested part (or both parts), if all of them were stored for this input by this version of the solver
+    pub fn get(&self, day: &Day, input: &str, part: Option<u8>) -> Option<Answers> {
+        let cached = self.load(day, input)?;
+        let wanted = |p: u8, answer: Option<String>| match part.unwrap_or(p) == p {
+            true => answer.map(Some),
+            false => Some(None),
+        };
+        Some(Answers { part_1: wanted(1, cached.part_1)?, part_2: wanted(2, cached.part_2)? })
+    }
+
+    //Stores the answers, keeping the ones stored before for the other part
+    pub fn put(&self, day: &Day, input: &str, answers: &Answers) -> io::Result<()> {
+        let cached = self.load(day, input).unwrap_or_default();
+        let entry = json!({
+            "version": solver_version(day),
+            "part1": answers.part_1.as_ref().or(cached.part_1.as_ref()),
+            "part2": answers.part_2.as_ref().or(cached.part_2.as_ref()),
+        });
+        let path = self.path(day, input);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, entry.to_string() + "\n")
+    }
+
+    fn load(&self, day: &Day, input: &str) -> Option<Answers> {
+        let entry = serde_json::from_str::<Value>(&fs::read_to_string(self.path(day, input)).ok()?).ok()?;
+        if entry["version"] != solver_version(day).as_str() {
+            return None;
+        }
+        let answer = |part: &str| entry[part].as_str().map(str::to_string);
+        Some(Answers { part_1: answer("part1"), part_2: answer("part2") })
+    }
+
+    //<dir>/<year>/dayNN-<input hash>.json
+    fn path(&self, day: &Day, input: &str) -> PathBuf {
+        self.dir.join(day.year.to_string()).join(format!("day{:02}-{}.json", day.day, input_hash(input)))
+    }
+}
+
+macro_rules! modules {
+    ($($module:ident),+) => { &[$((stringify!($module), include_str!(concat!(stringify!($module), ".rs")))),+] };
+}
+
+//Library modules the solvers compute their answers with, so an edit to one of them may change the answers of any day
+const SHARED_SOURCES: &[(&str, &str)] = modules!(lib, solution, parse, grid, point, direction, range_set, search, cycle, parallel, checkpoint);
+
+//Changes with the version number of the solver and with any edit to the source of its modules or the shared ones
+pub(crate) fn solver_version(day: &Day) -> String {
+    version_of(day, SHARED_SOURCES.iter().map(|(_, source)| *source))
+}
+
+fn version_of<'a>(day: &Day, shared_sources: impl Iterator<Item = &'a str>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(day.version);
+    day.sources.iter().for_each(|source| hasher.update(source));
+    shared_sources.for_each(|source| hasher.update(source));
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::{solver_version, version_of, AnswerCache, SHARED_SOURCES};
+    use crate::registry::{self, Day};
+    use crate::solution::Answers;
+
+    #[test]
+    fn answers_are_cached_per_input_and_version() {
+        let dir = env::temp_dir().join(format!("aoc-answer-cache-{}", process::id()));
+        let cache = AnswerCache::new(dir.clone());
+        let day = registry::get(2022, 1).unwrap();
+        let answer = |s: &str| Some(s.to_string());
+
+        assert_eq!(cache.get(day, "1\n", None), None);
+        cache.put(day, "1\n", &Answers { part_1: answer("1"), part_2: None }).unwrap();
+        assert_eq!(cache.get(day, "1\n", Some(1)), Some(Answers { part_1: answer("1"), part_2: None }));
+        assert_eq!(cache.get(day, "1\n", None), None);
+        cache.put(day, "1\n", &Answers { part_1: None, part_2: answer("2") }).unwrap();
+        assert_eq!(cache.get(day, "1\n", None), Some(Answers { part_1: answer("1"), part_2: answer("2") }));
+
+        assert_eq!(cache.get(day, "2\n", None), None);
+        let newer = Day { version: "2", ..*day };
+        assert_eq!(cache.get(&newer, "1\n", None), None);
+        let edited = Day { sources: &["//edited"], ..*day };
+        assert_eq!(cache.get(&edited, "1\n", None), None);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn editing_a_shared_module_changes_every_version() {
+        let day = registry::get(2022, 1).unwrap();
+        let edited = SHARED_SOURCES.iter().map(|&(module, source)| if module == "search" { "//edited" } else { source });
+        assert_ne!(version_of(day, edited), solver_version(day));
+
+        //Every module of the library besides the days is either shared by the solvers or only runs them
+        let runners = ["answers", "bench", "cache", "differential", "fetch", "generate", "input", "logging", "registry", "visualize"];
+        for module in include_str!("lib.rs").lines().filter_map(|line| line.strip_prefix("pub mod ")?.strip_suffix(';')) {
+            let shared = SHARED_SOURCES.iter().any(|&(shared, _)| shared == module);
+            assert!(module.starts_with("day") || shared || runners.contains(&module), "{} is neither shared nor a runner", module);
+        }
+    }
+}
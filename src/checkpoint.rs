@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Map, Value};
+
+use crate::cache::solver_version;
+use crate::input::input_hash;
+use crate::registry::Day;
+use crate::solution::{solve_with, Answers, Elapsed};
+use crate::{ParseError, Solution};
+
+//Completed units of work are written out at least this often, besides when the checkpoint is dropped
+const SAVE_INTERVAL: Duration = Duration::from_secs(2);
+
+//Checkpoints open in this process, for `save_open` to save when it is interrupted
+static OPEN: Mutex<Vec<Weak<Saved>>> = Mutex::new(Vec::new());
+
+//Results of the completed units of work of a long search, saved to a file every so often and when it is dropped, so
+//that an interrupted run can pick up where it stopped. Units are numbered by the search, and have to be numbered the
+//same way on every run
+pub struct Checkpoint(Arc<Saved>);
+
+struct Saved {
+    file: Option<PathBuf>,
+    input_hash: String,
+    version: String,
+    state: Mutex<State>,
+}
+
+struct State {
+    done: BTreeMap<usize, u64>,
+    unsaved: bool,
+    last_save: Instant,
+}
+
+impl Checkpoint {
+    //Keeps nothing, for solving without checkpoints
+    pub fn off() -> Self {
+        Checkpoint::new(None, String::new(), String::new(), BTreeMap::new())
+    }
+
+    //Saves completed units to `file`. When resuming, the units saved there before for the same input by the same version
+    //of the solver of `day` count as done; a checkpoint of another input or version, or one that cannot be read, is
+    //started over
+    pub fn open(file: PathBuf, day: &Day, input: &str, resume: bool) -> Self {
+        let (input_hash, version) = (input_hash(input), solver_version(day));
+        let done = match resume {
+            true => load(&file, &input_hash, &version).unwrap_or_default(),
+            false => BTreeMap::new(),
+        };
+        if !done.is_empty() {
+            log::info!("resuming from {} completed units in {}", done.len(), file.display());
+        }
+        let checkpoint = Checkpoint::new(Some(file), input_hash, version, done);
+        let mut open = OPEN.lock().unwrap();
+        open.retain(|saved| saved.strong_count() > 0);
+        open.push(Arc::downgrade(&checkpoint.0));
+        checkpoint
+    }
+
+    fn new(file: Option<PathBuf>, input_hash: String, version: String, done: BTreeMap<usize, u64>) -> Self {
+        let state = Mutex::new(State { done, unsaved: false, last_save: Instant::now() });
+        Checkpoint(Arc::new(Saved { file, input_hash, version, state }))
+    }
+
+    //Result of a unit completed before
+    pub fn get(&self, unit: usize) -> Option<u64> {
+        self.0.state.lock().unwrap().done.get(&unit).copied()
+    }
+
+    pub fn complete(&self, unit: usize, result: u64) {
+        let mut state = self.0.state.lock().unwrap();
+        state.done.insert(unit, result);
+        state.unsaved = true;
+        if state.last_save.elapsed() >= SAVE_INTERVAL {
+            self.0.save(&mut state);
+        }
+    }
+
+    //Removes the saved units once the whole search is done
+    pub fn finish(self) {
+        let mut state = self.0.state.lock().unwrap();
+        state.unsaved = false;
+        if let Some(file) = &self.0.file {
+            let _ = fs::remove_file(file);
+        }
+    }
+}
+
+impl Drop for Checkpoint {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.0.save(&mut state);
+    }
+}
+
+impl Saved {
+    //Writes out the completed units, if some were completed since the last time
+    fn save(&self, state: &mut State) {
+        let Some(file) = self.file.as_ref().filter(|_| state.unsaved) else { return };
+        let units = state.done.iter().map(|(unit, result)| (unit.to_string(), json!(result))).collect::<Map<_, _>>();
+        let checkpoint = json!({
+            "input": self.input_hash,
+            "version": self.version,
+            "units": units,
+        });
+        //Written next to the checkpoint and renamed over it, so an interruption never leaves half a file
+        let partial = file.with_extension("json.partial");
+        let saved = file.parent().map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&partial, checkpoint.to_string()))
+            .and_then(|_| fs::rename(&partial, file));
+        match saved {
+            Ok(()) => log::debug!("saved {} completed units to {}", state.done.len(), file.display()),
+            Err(e) => log::warn!("could not save checkpoint {}: {}", file.display(), e),
+        }
+        state.unsaved = false;
+        state.last_save = Instant::now();
+    }
+}
+
+//Saves the checkpoints open in this process, for when it is interrupted before they are dropped
+pub fn save_open() {
+    for saved in OPEN.lock().unwrap().iter().filter_map(Weak::upgrade) {
+        let mut state = saved.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        saved.save(&mut state);
+    }
+}
+
+//target/checkpoints/<year>/dayNN_partN.json
+pub fn checkpoint_path(year: u16, day: u8, part: u8) -> PathBuf {
+    PathBuf::from("target").join("checkpoints").join(year.to_string()).join(format!("day{:02}_part{}.json", day, part))
+}
+
+fn load(file: &Path, input_hash: &str, version: &str) -> io::Result<BTreeMap<usize, u64>> {
+    let checkpoint = serde_json::from_str::<Value>(&fs::read_to_string(file)?)?;
+    if checkpoint["input"] != input_hash || checkpoint["version"] != version {
+        return Ok(BTreeMap::new());
+    }
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, format!("{}: not a checkpoint", file.display()));
+    checkpoint["units"].as_object().ok_or_else(invalid)?.iter()
+        .map(|(unit, result)| Ok((unit.parse().map_err(|_| invalid())?, result.as_u64().ok_or_else(invalid)?)))
+        .collect()
+}
+
+//A solution with searches long enough to be worth resuming
+pub trait Resume: Solution {
+    //Solves a part, skipping the units of work completed in `checkpoint` and adding the ones it completes
+    fn resume(input: &Self::Input, part: u8, checkpoint: &Checkpoint) -> String;
+}
+
+//Solves the requested part (or both parts) like `solution::solve`, keeping the checkpoint opened for each part
+pub fn solve_resumable<S: Resume>(input: &str, part: Option<u8>, open: &dyn Fn(u8) -> Checkpoint) -> Result<(Answers, Elapsed), ParseError> {
+    solve_with::<S>(input, part, |parsed, p| {
+        let checkpoint = open(p);
+        let answer = S::resume(parsed, p, &checkpoint);
+        checkpoint.finish();
+        Ok(answer)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::{save_open, Checkpoint};
+    use crate::registry::{self, Day};
+
+    #[test]
+    fn checkpoints_keep_units_of_the_same_input_and_version() {
+        let file = env::temp_dir().join(format!("aoc-checkpoint-{}", process::id())).join("day16_part2.json");
+        let day = registry::get(2022, 16).unwrap();
+        let checkpoint = Checkpoint::open(file.clone(), day, "input", false);
+        checkpoint.complete(0, 7);
+        //As when interrupted
+        save_open();
+        assert_eq!(Checkpoint::open(file.clone(), day, "input", true).get(0), Some(7));
+        checkpoint.complete(3, 12);
+        drop(checkpoint);
+
+        let resumed = Checkpoint::open(file.clone(), day, "input", true);
+        assert_eq!((resumed.get(0), resumed.get(1), resumed.get(3)), (Some(7), None, Some(12)));
+        assert_eq!(Checkpoint::open(file.clone(), day, "other input", true).get(0), None);
+        assert_eq!(Checkpoint::open(file.clone(), day, "input", false).get(0), None);
+        let edited = Day { sources: &["//edited"], ..*day };
+        assert_eq!(Checkpoint::open(file.clone(), &edited, "input", true).get(0), None);
+
+        resumed.finish();
+        assert!(!file.exists());
+        let _ = fs::remove_dir_all(file.parent().unwrap());
+    }
+}
@@ -0,0 +1,65 @@
+use std::hash::Hash;
+
+use fxhash::FxHashMap;
+
+//States that repeat every `length` steps once `start` steps have been taken
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+    values: Vec<i64>, //metric after every step up to and including the first repeat
+}
+
+impl Cycle {
+    //Step whose state is the same as the one after `n` steps
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        match n < self.start {
+            true => n,
+            false => self.start + (n - self.start) % self.length,
+        }
+    }
+
+    //Metric after `n` steps, given that it changes by the same amount during every cycle
+    pub fn value_after(&self, n: usize) -> i64 {
+        if n < self.values.len() {
+            return self.values[n];
+        }
+        let per_cycle = self.values[self.start + self.length] - self.values[self.start];
+        let n_cycles = ((n - self.start) / self.length) as i64;
+        self.values[self.equivalent_step(n)] + n_cycles * per_cycle
+    }
+}
+
+//Steps `state` until the key of a state repeats, recording `metric` of every state along the way.
+//Gives up on the first state without a key, and steps forever if the keys never repeat.
+pub fn find_cycle<S, K: Eq + Hash>(mut state: S, mut step: impl FnMut(&mut S), mut key: impl FnMut(&S) -> Option<K>, mut metric: impl FnMut(&S) -> i64) -> Option<Cycle> {
+    let mut seen = FxHashMap::default();
+    let mut values = vec![];
+    for n in 0.. {
+        values.push(metric(&state));
+        if let Some(start) = seen.insert(key(&state)?, n) {
+            return Some(Cycle { start, length: n - start, values });
+        }
+        step(&mut state);
+    }
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_cycle;
+
+    #[test]
+    fn cycles_extrapolate_metrics() {
+        //3, 1, 2, 0, 1, 2, 0, ... counting the total distance travelled
+        let cycle = find_cycle((3_i64, 0), |(x, total)| {
+            let next = (*x + 1) % 3;
+            *total += (next - *x).abs();
+            *x = next;
+        }, |(x, _)| Some(*x), |(_, total)| *total).unwrap();
+        assert_eq!((cycle.start, cycle.length), (1, 3));
+        assert_eq!(cycle.equivalent_step(7), 1);
+        assert_eq!(cycle.value_after(2), 3);
+        assert_eq!(cycle.value_after(1 + 3 * 1_000_000 + 2), 5 + 4 * 1_000_000);
+    }
+}
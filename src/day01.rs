@@ -1,15 +1,43 @@
 use std::str::FromStr;
-use aoc2022::parse_to_vec;
+use crate::{parse_sections, ParseError, Scanner, Solution};
+use crate::generate::{Generate, Rng};
 
 const N_HIGHEST_CALORIE_ELVES : u32 = 3;
 
-const INPUT: &str = include_str!("../input/2022/day01.txt");
+pub struct Day01;
 
-fn main(){
-    let elves : Vec<Elf> = parse_to_vec(INPUT, "\n\n").unwrap();
+impl Solution for Day01 {
+    type Input = Vec<Elf>;
+    type Part1 = u32;
+    type Part2 = u32;
 
-    println!("Part 1: {}", elves.iter().map(|e| e.cals).max().unwrap());
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let elves: Vec<Elf> = parse_sections(input)?;
+        match elves.is_empty() {
+            true => Err(ParseError::end(input, "the calories of an elf")),
+            false => Ok(elves),
+        }
+    }
+
+    fn part1(elves: &Self::Input) -> u32 {
+        elves.iter().map(|e| e.cals).max().unwrap()
+    }
 
+    fn part2(elves: &Self::Input) -> u32 {
+        most_calories(elves)
+    }
+}
+
+impl Generate for Day01 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let elves = (0..size.max(1)).map(|_| {
+            (0..rng.range(1..=6)).map(|_| format!("{}\n", rng.range(1000..=60000))).collect::<String>()
+        }).collect::<Vec<String>>();
+        elves.join("\n")
+    }
+}
+
+fn most_calories(elves: &[Elf]) -> u32 {
     let (mut lowest_cals, mut lowest_cals_index) = (0, 0);
     let mut most_cal_elves = [0; N_HIGHEST_CALORIE_ELVES as usize];
 
@@ -22,23 +50,25 @@ fn main(){
         }
     });
 
-    println!("Part 2: {}", most_cal_elves.iter().sum::<u32>());
+    most_cal_elves.iter().sum::<u32>()
 }
 
 
 #[derive(Debug)]
-struct Elf{
+pub struct Elf{
     pub cals : u32
 }
 
 impl FromStr for Elf{
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let cals = s.split("\n")
-            .filter_map(|cal_str| {
-                cal_str.parse::<u32>().ok()
-            }).sum();
+        let mut scan = Scanner::new(s);
+        let mut cals = 0_u32;
+        while !scan.is_empty() {
+            cals = cals.checked_add(scan.integer("a calorie count")?)
+                .ok_or_else(|| ParseError::at(s, s, "calories adding up to at most 4294967295"))?;
+        }
         Ok(Elf{cals})
     }
 }
\ No newline at end of file
@@ -1,31 +1,44 @@
-extern crate core;
-
 use std::str::FromStr;
 
-use aoc2022::{parse_to_vec};
+use crate::{parse_to_vec, ParseError, Scanner, Solution};
+use crate::generate::{Generate, Rng};
+
+pub struct Day02;
 
-const INPUT: &str = include_str!("../input/2022/day02.txt");
+impl Solution for Day02 {
+    type Input = Vec<Round>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_to_vec(input, "\n")
+    }
 
-fn main() {
-    // Part 1
-    let mut rounds: Vec<Round> = parse_to_vec(INPUT, "\n").unwrap();
-    println!("Part 1: {}", rounds.iter().map(|r| r.score() as u32).sum::<u32>());
+    fn part1(rounds: &Self::Input) -> u32 {
+        rounds.iter().map(|r| r.score() as u32).sum::<u32>()
+    }
 
-    // Part 2
-    // Resolve the misunderstanding
-    rounds.iter_mut().for_each(|r| {
-        r.1 = match r.1 {
-            0 => losing_counter(r.0),
-            1 => r.0,
-            2 => winning_counter(r.0),
-            _ => panic!("invalid input"),
-        };
-    });
+    fn part2(rounds: &Self::Input) -> u32 {
+        // Resolve the misunderstanding
+        rounds.iter().map(|r| {
+            let you = match r.1 {
+                0 => losing_counter(r.0),
+                1 => r.0,
+                2 => winning_counter(r.0),
+                _ => panic!("invalid input"),
+            };
+            Round(r.0, you)
+        }).map(|r| r.score() as u32).sum::<u32>()
+    }
+}
 
-    println!("Part 2: {}", rounds.iter().map(|r| r.score() as u32).sum::<u32>());
+impl Generate for Day02 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size.max(1)).map(|_| format!("{} {}\n", rng.pick(&['A', 'B', 'C']), rng.pick(&['X', 'Y', 'Z']))).collect()
+    }
 }
 
-struct Round(u8, u8); // (opponent, you) where 0 = rock, 1 = paper, 2 = scissors
+pub struct Round(u8, u8); // (opponent, you) where 0 = rock, 1 = paper, 2 = scissors
 
 impl Round {
     fn score(&self) -> u8{
@@ -34,15 +47,14 @@ impl Round {
 }
 
 impl FromStr for Round {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() != 3 {
-            return Err(());
-        }
-        let opp = s.chars().nth(0).unwrap() as u8 - 65; //ASCII: A = 65
-        let you = s.chars().nth(2).unwrap() as u8 - 88; //ASCII: X = 88
-        Ok(Round(opp,you))
+        let mut scan = Scanner::new(s);
+        let opp = scan.one_of(&["A", "B", "C"])?;
+        let you = scan.one_of(&["X", "Y", "Z"])?;
+        scan.end()?;
+        Ok(Round(opp.as_bytes()[0] - b'A', you.as_bytes()[0] - b'X'))
     }
 }
 
@@ -59,7 +71,7 @@ fn move_score(m : u8) -> u8 {
 }
 
 fn round_score(opp : u8, you : u8) -> u8 {
-    return if opp == you {
+    if opp == you {
         3
     } else if you == winning_counter(opp) {
         6
@@ -1,47 +1,90 @@
 use std::str::FromStr;
 use itertools::Itertools;
 
-use aoc2022::parse_to_vec;
-
-const INPUT: &str = include_str!("../input/2022/day03.txt");
-
-fn main() {
-    let rucksacks: Vec<Rucksack> = parse_to_vec(INPUT, "\n").unwrap();
-
-    let sum_misplaced_items = rucksacks.iter()
-        .map(|r| r.misplaced_item.unwrap()).sum::<u32>();
+use crate::{parse_to_vec, ParseError, Solution};
+use crate::generate::{Generate, Rng};
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Vec<Rucksack>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let rucksacks: Vec<Rucksack> = parse_to_vec(input, "\n")?;
+        //The last rucksack of every group of three has to share the badge with the two before it
+        let lines = input.split('\n').filter(|line| !line.trim().is_empty());
+        for ((r1, r2, r3), line) in rucksacks.iter().tuples().zip(lines.skip(2).step_by(3)) {
+            if find_badge(r1, r2, r3).is_none() {
+                return Err(ParseError::at(input, line, "a rucksack sharing a badge with the two before it"));
+            }
+        }
+        Ok(rucksacks)
+    }
 
-    println!("Part 1: {}", sum_misplaced_items);
+    fn part1(rucksacks: &Self::Input) -> u32 {
+        rucksacks.iter()
+            .map(|r| r.misplaced_item).sum::<u32>()
+    }
 
-    let sum_badges = rucksacks.iter().tuples()
-        .map(|(r1,r2,r3)| find_badge(&r1, &r2, &r3).unwrap()).sum::<u32>();
+    fn part2(rucksacks: &Self::Input) -> u32 {
+        rucksacks.iter().tuples()
+            .map(|(r1,r2,r3)| find_badge(r1, r2, r3).unwrap()).sum::<u32>()
+    }
+}
 
-    println!("Part 2: {}", sum_badges);
+impl Generate for Day03 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let items = ('a'..='z').chain('A'..='Z').collect_vec();
+        let mut rucksacks = String::new();
+        for _ in 0..size.div_ceil(3).max(1) {
+            let badge = *rng.pick(&items);
+            for _ in 0..3 {
+                //Both compartments share an item, and the badge is in every rucksack of the group
+                let misplaced = *rng.pick(&items);
+                let half = rng.range(2..=16);
+                let mut first = vec![misplaced, badge];
+                let mut second = vec![misplaced];
+                first.extend((2..half).map(|_| *rng.pick(&items)));
+                second.extend((1..half).map(|_| *rng.pick(&items)));
+                rng.shuffle(&mut first);
+                rng.shuffle(&mut second);
+                rucksacks.extend(first.into_iter().chain(second));
+                rucksacks.push('\n');
+            }
+        }
+        rucksacks
+    }
 }
 
-struct Rucksack {
+pub struct Rucksack {
     pub item_present: [bool; 53],
-    pub misplaced_item: Option<u32>,
+    pub misplaced_item: u32,
 }
 
 impl FromStr for Rucksack {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() == 0 {
-            return Err(());
+        if s.is_empty() {
+            return Err(ParseError::end(s, "a rucksack"));
+        }
+        if let Some((i, c)) = s.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+            return Err(ParseError::at(s, &s[i..i + c.len_utf8()], "an item (a-z or A-Z)"));
         }
         let (s1, s2) = s.split_at(s.len() / 2);
         let (mut item_in_c1, mut item_in_c2) = ([false; 53], [false; 53]);
 
-        s1.chars().for_each(|c| item_in_c1[(item_value_mapper(c))] = true);
-        s2.chars().for_each(|c| item_in_c2[(item_value_mapper(c))] = true);
+        s1.chars().for_each(|c| item_in_c1[item_value_mapper(c)] = true);
+        s2.chars().for_each(|c| item_in_c2[item_value_mapper(c)] = true);
 
         let misplaced_item = item_in_c1.iter()
             .zip(item_in_c2.iter())
             .zip(0..53)
             .find(|((a, b), _)| **a && **b)
-            .map(|(_, i)| i);
+            .map(|(_, i)| i)
+            .ok_or_else(|| ParseError::at(s, s, "a rucksack with an item in both compartments"))?;
 
         let item_present = item_in_c1.iter().zip(item_in_c2.iter())
             .map(|(a, b)| *a || *b)
@@ -52,21 +95,16 @@ impl FromStr for Rucksack {
 }
 
 fn item_value_mapper(c: char) -> usize {
-    if c <= 'z' && c >= 'a' {
-        return c as usize - 'a' as usize + 1;
-    }
-    if c <= 'Z' && c >= 'A' {
-        return c as usize - 'A' as usize + 27;
+    if c.is_ascii_lowercase() {
+        c as usize - 'a' as usize + 1
+    } else if c.is_ascii_uppercase() {
+        c as usize - 'A' as usize + 27
     } else {
         panic!("invalid char: {}", c)
     }
 }
 
-fn find_badge(r1: &Rucksack, r2: &Rucksack, r3: &Rucksack) -> Result<u32, ()> {
-    for i in 0..53 {
-        if r1.item_present[i] && r2.item_present[i] && r3.item_present[i] {
-            return Ok((i) as u32);
-        }
-    }
-    Err(())
+fn find_badge(r1: &Rucksack, r2: &Rucksack, r3: &Rucksack) -> Option<u32> {
+    (0..53).find(|&i| r1.item_present[i] && r2.item_present[i] && r3.item_present[i])
+        .map(|i| i as u32)
 }
\ No newline at end of file
@@ -1,42 +1,66 @@
-use std::str::{FromStr, Split};
+use std::str::FromStr;
 
-const INPUT: &str = include_str!("../input/2022/day04.txt");
+use crate::{parse_to_vec, ParseError, RangeSet, Scanner, Solution};
+use crate::generate::{Generate, Rng};
 
-pub fn main() {
-    let range_pairs: Vec<RangePair> = aoc2022::parse_to_vec(INPUT, "\n").unwrap();
+pub struct Day04;
 
-    println!("Part 1: {}", range_pairs.iter().filter(|r| { r.inclusive() }).count());
-    println!("Part 2: {}", range_pairs.iter().filter(|r| { r.overlaps() }).count());
+impl Solution for Day04 {
+    type Input = Vec<RangePair>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_to_vec(input, "\n")
+    }
+
+    fn part1(range_pairs: &Self::Input) -> usize {
+        range_pairs.iter().filter(|r| { r.inclusive() }).count()
+    }
+
+    fn part2(range_pairs: &Self::Input) -> usize {
+        range_pairs.iter().filter(|r| { r.overlaps() }).count()
+    }
+}
+
+impl Generate for Day04 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size.max(1)).map(|_| {
+            let (a_min, b_min) = (rng.range(1..=90), rng.range(1..=90));
+            format!("{}-{},{}-{}\n", a_min, rng.range(a_min..=99), b_min, rng.range(b_min..=99))
+        }).collect()
+    }
 }
 
 pub struct RangePair {
-    a_min: u32, a_max: u32, b_min: u32, b_max: u32,
+    a: RangeSet<u32>,
+    b: RangeSet<u32>,
 }
 
 impl FromStr for RangePair {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split = s.split(",");
-        let mut split_1 = split.next().ok_or(())?.split("-");
-        let mut split_2 = split.next().ok_or(())?.split("-");
-
-        let next_u32 = |split: &mut Split<&str>| -> Result<u32, Self::Err> {
-            split.next().ok_or(())?.parse::<u32>().map_err(|_| ())
+        let range = |scan: &mut Scanner| -> Result<RangeSet<u32>, Self::Err> {
+            let min = scan.integer("a section number")?;
+            scan.literal("-")?;
+            Ok(RangeSet::from(min..=scan.integer("a section number")?))
         };
-
-        let (a_min, a_max) = (next_u32(&mut split_1)?, next_u32(&mut split_1)?);
-        let (b_min, b_max) = (next_u32(&mut split_2)?, next_u32(&mut split_2)?);
-        Ok(RangePair { a_min, a_max, b_min, b_max })
+        let mut scan = Scanner::new(s);
+        let a = range(&mut scan)?;
+        scan.literal(",")?;
+        let b = range(&mut scan)?;
+        scan.end()?;
+        Ok(RangePair { a, b })
     }
 }
 
 impl RangePair {
     pub fn inclusive(&self) -> bool {
-        self.a_min <= self.b_min && self.b_max <= self.a_max || self.b_min <= self.a_min && self.a_max <= self.b_max
+        self.a.difference(&self.b).is_empty() || self.b.difference(&self.a).is_empty()
     }
 
     pub fn overlaps(&self) -> bool {
-        !(self.a_min > self.b_max || self.b_min > self.a_max)
+        !self.a.intersection(&self.b).is_empty()
     }
 }
\ No newline at end of file
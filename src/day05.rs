@@ -1,50 +1,116 @@
 use std::collections::VecDeque;
+use std::num::NonZeroUsize;
 use std::str::FromStr;
-use aoc2022::parse_to_vec;
 
-const INPUT: &str = include_str!("../input/2022/day05.txt");
+use itertools::Itertools;
 
-pub fn main(){
-    let mut input_split = INPUT.split("\n\n");
+use crate::{parse_to_vec, sections, ParseError, Scanner, Solution};
+use crate::generate::{Generate, Rng};
 
-    let crate_stacks_input = input_split.next().unwrap();
-    let crane_operations = input_split.next().unwrap();
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = (VecDeque<CrateStack>, Vec<CraneOp>);
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1((crate_stacks, crane_operations): &Self::Input) -> String {
+        let mut crate_stacks = crate_stacks.clone();
+
+        //Do the crane ops
+        crane_operations.iter().for_each(|op| {
+            let popped_crates = crate_stacks.get_mut(op.from).unwrap().pop_n(op.n_crates);
+            crate_stacks.get_mut(op.to).unwrap().push(popped_crates);
+        });
+
+        top_crates_to_string(&crate_stacks)
+    }
+
+    fn part2((crate_stacks, crane_operations): &Self::Input) -> String {
+        let mut crate_stacks = crate_stacks.clone();
+
+        crane_operations.iter().for_each(|op| {
+            let mut popped_crates = crate_stacks.get_mut(op.from).unwrap().pop_n(op.n_crates);
+            popped_crates.reverse(); //CrateMover 9001
+            crate_stacks.get_mut(op.to).unwrap().push(popped_crates);
+        });
+
+        top_crates_to_string(&crate_stacks)
+    }
+}
+
+impl Generate for Day05 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut heights = (0..9).map(|_| rng.range(1..=8)).collect::<Vec<usize>>();
+        heights[0] = heights[0].max(2);
+        let drawing = (0..*heights.iter().max().unwrap()).rev().map(|level| {
+            (0..9).map(|stack| match heights[stack] > level {
+                true => format!("[{}]", rng.range(b'A'..=b'Z') as char),
+                false => "   ".to_string(),
+            }).join(" ")
+        }).join("\n");
+
+        //Every stack keeps at least one crate, so there is always a top crate to read
+        let mut operations = vec![];
+        while operations.len() < size.max(1) {
+            let (from, to) = (rng.range(0..=8), rng.range(0..=8));
+            if from == to || heights[from] < 2 {
+                continue;
+            }
+            let n_crates = rng.range(1..=heights[from] - 1);
+            heights[from] -= n_crates;
+            heights[to] += n_crates;
+            operations.push(format!("move {} from {} to {}\n", n_crates, from + 1, to + 1));
+        }
+        format!("{}\n 1   2   3   4   5   6   7   8   9 \n\n{}", drawing, operations.concat())
+    }
+}
+
+fn parse(input: &str) -> Result<(VecDeque<CrateStack>, Vec<CraneOp>), ParseError> {
+    let [crate_stacks_input, crane_operations] = sections(input)[..] else {
+        return Err(ParseError::end(input, "a blank line followed by the crane operations"));
+    };
 
     let crate_stacks_lines = crate_stacks_input.split('\n').rev().skip(1).collect::<Vec<&str>>();
     let crate_char_indices : [usize; 9] = [1,5,9,13,17,21,25,29,33]; //indices of the crate characters in the lines
 
     let crate_stacks = (0..9).map(|i|{
         let crates = crate_stacks_lines.iter()
-            .map(|line| line.chars().nth(crate_char_indices[i]).unwrap())
-            .filter(|c| !c.is_whitespace())
-            .collect::<VecDeque<char>>();
-        CrateStack{
+            .map(|line| line.chars().nth(crate_char_indices[i])
+                .ok_or_else(|| ParseError::at(input, &line[line.len()..], format!("a crate or space in column {}", crate_char_indices[i] + 1))))
+            .filter_ok(|c| !c.is_whitespace())
+            .collect::<Result<VecDeque<char>, ParseError>>()?;
+        Ok(CrateStack{
             crates
+        })
+    }).collect::<Result<VecDeque<CrateStack>, ParseError>>()?;
+
+    let operations : Vec<CraneOp> = parse_to_vec(crane_operations, "\n").map_err(|e| e.within(input, crane_operations))?;
+
+    //Both cranes move as many crates, so the stacks are as high after every operation in either part
+    let mut heights = crate_stacks.iter().map(|stack| stack.crates.len()).collect_vec();
+    let lines = crane_operations.split('\n').filter(|line| !line.trim().is_empty());
+    for (op, line) in operations.iter().zip(lines) {
+        if op.from >= heights.len() || op.to >= heights.len() {
+            return Err(ParseError::at(input, line, format!("stacks numbered 1 to {}", heights.len())));
         }
-    }).collect::<VecDeque<CrateStack>>();
-
-    let crane_operations : Vec<CraneOp> = parse_to_vec(crane_operations, "\n").unwrap();
-
-    //Do the crane ops
-    let mut crate_stacks_1 = crate_stacks.clone();
-    crane_operations.iter().for_each(|op| {
-        let popped_crates = crate_stacks_1.get_mut(op.from).unwrap().pop_n(op.n_crates);
-        crate_stacks_1.get_mut(op.to).unwrap().push(popped_crates);
-    });
-
-    let mut crate_stacks_2 = crate_stacks.clone();
-    crane_operations.iter().for_each(|op| {
-        let mut popped_crates = crate_stacks_2.get_mut(op.from).unwrap().pop_n(op.n_crates);
-        popped_crates.reverse(); //CrateMover 9001
-        crate_stacks_2.get_mut(op.to).unwrap().push(popped_crates);
-    });
-
-    let top_crate_to_string = |cs : VecDeque<CrateStack>| {
-        cs.iter().map(|s| *s.crates.get(s.crates.len()-1).unwrap()).collect::<String>()
-    };
+        if op.n_crates > heights[op.from] {
+            return Err(ParseError::at(input, line, format!("a move of at most the {} crates on stack {}", heights[op.from], op.from + 1)));
+        }
+        heights[op.from] -= op.n_crates;
+        heights[op.to] += op.n_crates;
+    }
 
-    println!("Part 1: {}", top_crate_to_string(crate_stacks_1));
-    println!("Part 2: {}", top_crate_to_string(crate_stacks_2));
+    Ok((crate_stacks, operations))
+}
+
+fn top_crates_to_string(crate_stacks: &VecDeque<CrateStack>) -> String {
+    //An empty stack has no crate on top to read
+    crate_stacks.iter().filter_map(|s| s.crates.back()).collect::<String>()
 }
 
 #[derive(Clone)]
@@ -69,15 +135,17 @@ pub struct CraneOp {
 }
 
 impl FromStr for CraneOp {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let split = s.split(' ').collect::<Vec<&str>>();
-        if split.len() != 6 { return Err(()); }
-
-        let n_crates = split.get(1).unwrap().parse::<usize>().unwrap();
-        let from = split.get(3).unwrap().parse::<usize>().unwrap() - 1;
-        let to = split.get(5).unwrap().parse::<usize>().unwrap() - 1;
+        let mut scan = Scanner::new(s);
+        scan.literal("move")?;
+        let n_crates = scan.integer("a number of crates")?;
+        scan.literal("from")?;
+        let from = scan.integer::<NonZeroUsize>("a stack number")?.get() - 1;
+        scan.literal("to")?;
+        let to = scan.integer::<NonZeroUsize>("a stack number")?.get() - 1;
+        scan.end()?;
 
         Ok(Self{n_crates, from, to})
     }
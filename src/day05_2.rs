@@ -1,58 +1,65 @@
+use std::num::NonZeroUsize;
 use std::str::FromStr;
-use std::time::Instant;
 
 use itertools::Itertools;
 
-use aoc2022::parse_to_vec;
+use crate::{parse_to_vec, sections, ParseError, Scanner, Solution};
 
-const INPUT: &str = include_str!("../input/2022/day05.txt");
+//Alternative to Day05: tracks where each top crate came from by executing the crane operations in reverse
+pub struct Day05Reverse;
 
-pub fn main() {
-    let start = Instant::now();
-    let mut input_split = INPUT.split("\n\n");
+impl Solution for Day05Reverse {
+    type Input = (Vec<Vec<char>>, Vec<CraneOp>);
+    type Part1 = String;
+    type Part2 = String;
 
-    let crate_stacks_input = input_split.next().unwrap();
-    let crane_operations = input_split.next().unwrap();
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
 
-    let crate_stacks_lines = crate_stacks_input.split('\n').rev().skip(1).collect::<Vec<&str>>();
+    fn part1((crate_stacks, rev_crane_ops): &Self::Input) -> String {
+        //Positions are indexed based on their current stack
+        let mut positions = (0..crate_stacks.len()).map(|i| vec![Position::new(i)]).collect::<Vec<Vec<Position>>>();
 
-    let n_crates = (crate_stacks_lines[0].len() + 1) / 4;
-    let crate_char_indices = (0..n_crates).map(|i| 1 + (4 * i)).collect::<Vec<usize>>();
+        //Execute all crane operations (in reverse)
+        solve(rev_crane_ops, &mut positions, true);
 
-    let crate_stacks = (0..n_crates).map(|i| {
-        crate_stacks_lines.iter()
-            .map(|line| line.chars().nth(crate_char_indices[i]).unwrap())
-            .filter(|c| !c.is_whitespace())
-            .collect::<Vec<char>>()
-    }).collect::<Vec<Vec<char>>>();
-
-    let crane_ops: Vec<CraneOp> = parse_to_vec(crane_operations, "\n").unwrap();
-    //We will simulate the crane operations in reverse so we need to reverse the operation and the order
-    let rev_crane_ops: Vec<CraneOp> = crane_ops.iter().rev().map(|op| op.reverse()).collect();
+        to_string(&positions, crate_stacks)
+    }
 
-    println!("Parsed in {:?}", start.elapsed());
+    fn part2((crate_stacks, rev_crane_ops): &Self::Input) -> String {
+        //Same as part 1 but without reversing the crate order when executing the crane operations
+        let mut positions = (0..crate_stacks.len()).map(|i| vec![Position::new(i)]).collect::<Vec<Vec<Position>>>();
+        solve(rev_crane_ops, &mut positions, false);
 
-    let start_part_1 = Instant::now();
-    //Positions are indexed based on their current stack
-    let mut positions_1 = (0..n_crates).map(|i| vec![Position::new(i)]).collect::<Vec<Vec<Position>>>();
+        to_string(&positions, crate_stacks)
+    }
+}
 
-    //Execute all crane operations (in reverse)
-    solve(&rev_crane_ops, &mut positions_1, true);
+fn parse(input: &str) -> Result<(Vec<Vec<char>>, Vec<CraneOp>), ParseError> {
+    let [crate_stacks_input, crane_operations] = sections(input)[..] else {
+        return Err(ParseError::end(input, "a blank line followed by the crane operations"));
+    };
 
-    let dur_part_1 = start_part_1.elapsed();
+    let crate_stacks_lines = crate_stacks_input.split('\n').rev().skip(1).collect::<Vec<&str>>();
+    let bottom_line = crate_stacks_lines.first().ok_or_else(|| ParseError::end(crate_stacks_input, "a drawing of the crate stacks"))?;
 
-    println!("Part 1: {}", to_string(&positions_1, &crate_stacks));
-    println!("in {:?}", dur_part_1);
+    let n_crates = (bottom_line.len() + 1) / 4;
+    let crate_char_indices = (0..n_crates).map(|i| 1 + (4 * i)).collect::<Vec<usize>>();
 
-    //Do the same for part 2 but without reversing the crate order when executing the crane operations
-    let start_part_2 = Instant::now();
+    let crate_stacks = (0..n_crates).map(|i| {
+        crate_stacks_lines.iter()
+            .map(|line| line.chars().nth(crate_char_indices[i])
+                .ok_or_else(|| ParseError::at(input, &line[line.len()..], format!("a crate or space in column {}", crate_char_indices[i] + 1))))
+            .filter_ok(|c| !c.is_whitespace())
+            .collect::<Result<Vec<char>, ParseError>>()
+    }).collect::<Result<Vec<Vec<char>>, ParseError>>()?;
 
-    let mut positions_2 = (0..n_crates).map(|i| vec![Position::new(i)]).collect::<Vec<Vec<Position>>>();
-    solve(&rev_crane_ops, &mut positions_2, false);
-    let dur_part_2 = start_part_2.elapsed();
+    let crane_ops: Vec<CraneOp> = parse_to_vec(crane_operations, "\n").map_err(|e| e.within(input, crane_operations))?;
+    //We will simulate the crane operations in reverse so we need to reverse the operation and the order
+    let rev_crane_ops: Vec<CraneOp> = crane_ops.iter().rev().map(|op| op.reverse()).collect();
 
-    println!("Part 2: {}", to_string(&positions_2, &crate_stacks));
-    println!("in {:?}", dur_part_2);
+    Ok((crate_stacks, rev_crane_ops))
 }
 
 pub struct CraneOp {
@@ -62,15 +69,17 @@ pub struct CraneOp {
 }
 
 impl FromStr for CraneOp {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let split = s.split(' ').collect::<Vec<&str>>();
-        if split.len() != 6 { return Err(()); }
-
-        let n_crates = split.get(1).unwrap().parse::<usize>().unwrap();
-        let from = split.get(3).unwrap().parse::<usize>().unwrap() - 1;
-        let to = split.get(5).unwrap().parse::<usize>().unwrap() - 1;
+        let mut scan = Scanner::new(s);
+        scan.literal("move")?;
+        let n_crates = scan.integer("a number of crates")?;
+        scan.literal("from")?;
+        let from = scan.integer::<NonZeroUsize>("a stack number")?.get() - 1;
+        scan.literal("to")?;
+        let to = scan.integer::<NonZeroUsize>("a stack number")?.get() - 1;
+        scan.end()?;
 
         Ok(Self { n_crates, from, to })
     }
@@ -106,8 +115,6 @@ impl Position {
                 self.curr_stack = op.to;
                 if reverse {
                     self.n_crates_on_top = op.n_crates - self.n_crates_on_top - 1;
-                } else {
-                    self.n_crates_on_top = self.n_crates_on_top;
                 }
             } else {
                 //Not enough crates are moved to affect this position's stack
@@ -120,7 +127,7 @@ impl Position {
     }
 }
 
-pub fn solve(crane_ops: &Vec<CraneOp>, positions: &mut Vec<Vec<Position>>, reverse: bool) {
+pub fn solve(crane_ops: &[CraneOp], positions: &mut [Vec<Position>], reverse: bool) {
     let mut changed_positions = vec![];
     crane_ops.iter().for_each(|op| {
         //Execute operation in all positions located in the "from" stack
@@ -152,14 +159,13 @@ pub fn solve(crane_ops: &Vec<CraneOp>, positions: &mut Vec<Vec<Position>>, rever
     });
 }
 
-pub fn to_string(position: &Vec<Vec<Position>>, crate_stacks: &Vec<Vec<char>>) -> String {
+pub fn to_string(position: &[Vec<Position>], crate_stacks: &[Vec<char>]) -> String {
     //Search the correct char for each position and order them in the original stack order
     position.iter().flatten()
         .sorted_by(|a, b| a.org_stack.cmp(&b.org_stack))
         .map(|p| {
             let crate_stack = &crate_stacks[p.curr_stack];
-            let crate_char = crate_stack[crate_stack.len() - 1 - p.n_crates_on_top];
-            crate_char
+            crate_stack[crate_stack.len() - 1 - p.n_crates_on_top]
         }).collect()
 }
 
@@ -1,59 +1,70 @@
 use std::ops::Range;
-use std::time::Instant;
 
 use itertools::Itertools;
 
-const INPUT: &str = include_str!("../input/2022/day06.txt");
-const INPUT_EXTRA: &str = include_str!("../input/2022/day06_extra.txt");
+use crate::{parallel, ParseError, Solution};
+use crate::solution::NoAnswer;
+use crate::generate::{Generate, Rng};
 
-fn main() {
-    println!("{} chars", INPUT.len());
-    let char_vec = INPUT.chars().filter(|c| {
-        *c as u32 >= 32 && *c as u32 <= 126
-    }).collect::<Vec<char>>();
+pub struct Day06;
 
-    println!("Part 1: {}", find_marker_naive(&char_vec, 4).unwrap());
-    println!("Part 2: {}", find_marker_naive(&char_vec, 14).unwrap());
-    println!();
-    //Extra
-    println!("{} chars", INPUT_EXTRA.len());
-    let char_vec = INPUT_EXTRA.chars().filter(|c| {
-        *c as u32 >= 32 && *c as u32 <= 126
-    }).collect::<Vec<char>>();
-    let n_threads = 8;
+impl Solution for Day06 {
+    type Input = Vec<char>;
+    type Part1 = Result<usize, NoAnswer>;
+    type Part2 = Result<usize, NoAnswer>;
 
-    println!("{} threads", n_threads);
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(input.chars().filter(|c| {
+            *c as u32 >= 32 && *c as u32 <= 126
+        }).collect::<Vec<char>>())
+    }
+
+    fn part1(chars: &Self::Input) -> Result<usize, NoAnswer> {
+        marker(chars, 4)
+    }
 
-    let start = Instant::now();
-    println!("Part 1: {} ({:?})", find_range_markers_mt(&char_vec, 94..95, n_threads).iter().sum::<usize>(), start.elapsed());
+    fn part2(chars: &Self::Input) -> Result<usize, NoAnswer> {
+        marker(chars, 14)
+    }
+}
 
-    let start = Instant::now();
-    println!("Part 2: {} ({:?})", find_range_markers_mt(&char_vec, 1..95, n_threads).iter().sum::<usize>(), start.elapsed());
+fn marker(chars: &[char], n_unique_chars: usize) -> Result<usize, NoAnswer> {
+    find_range_markers_mt(chars, n_unique_chars..n_unique_chars + 1)[0]
+        .ok_or_else(|| NoAnswer(format!("a marker of {} different characters", n_unique_chars)))
 }
 
-fn find_marker_naive(input: &Vec<char>, n_unique_chars: usize) -> Option<usize> {
+impl Generate for Day06 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        //Letters repeat a lot, apart from a single stretch of 14 different ones
+        let mut signal = (0..size.max(1)).map(|_| rng.range(b'a'..=b'd') as char).collect_vec();
+        let mut marker = ('a'..='z').collect_vec();
+        rng.shuffle(&mut marker);
+        let at = rng.range(0..=signal.len());
+        signal.splice(at..at, marker[..14].iter().copied());
+        signal.into_iter().chain(['\n']).collect()
+    }
+}
+
+pub fn find_marker_naive(input: &[char], n_unique_chars: usize) -> Option<usize> {
     //Returns the index after which the first substring containing n unique characters is found
     input.windows(n_unique_chars).enumerate()
         .find(|(_, window)| {
-            window.iter().map(|c| c).unique().count() == n_unique_chars
+            window.iter().unique().count() == n_unique_chars
         })
         .map(|(i, _)| i + n_unique_chars)
 }
 
-fn find_marker_efficient(input: &Vec<char>, start: usize, stop: usize, n_unique_chars: usize) -> Option<usize>{
+pub fn find_marker_efficient(input: &[char], start: usize, stop: usize, n_unique_chars: usize) -> Option<usize>{
     let mut last_seen = [None; 95];
     let mut potential_start = start as i64 -1;
     for i in start..stop{
         let c = input[i];
         let index = c as usize - ' ' as usize;
         let prev_last_seen = last_seen[index];
-        match prev_last_seen{
-            None => (),
-            Some(prev_i) => {
-                if i < prev_i + n_unique_chars{
-                    //This character makes the substring not unique, the next potential start is after the previous occurrence of this character
-                    potential_start = i64::max(potential_start, prev_i as i64);
-                }
+        if let Some(prev_i) = prev_last_seen{
+            if i < prev_i + n_unique_chars{
+                //This character makes the substring not unique, the next potential start is after the previous occurrence of this character
+                potential_start = i64::max(potential_start, prev_i as i64);
             }
         }
         last_seen[index] = Some(i);
@@ -64,7 +75,9 @@ fn find_marker_efficient(input: &Vec<char>, start: usize, stop: usize, n_unique_
     None
 }
 
-fn find_range_markers_mt(input: &Vec<char>, range: Range<usize>, n_threads: usize) -> Vec<usize> {
+//First marker of every length in `range` (if there is one), searching a block of the input per thread of the pool it runs on
+pub fn find_range_markers_mt(input: &[char], range: Range<usize>) -> Vec<Option<usize>> {
+    let n_threads = parallel::n_threads();
     let blocks = create_blocks(input.len(), range.end, n_threads);
     let mut results = (0..n_threads).map(|_| vec![]).collect::<Vec<Vec<Option<usize>>>>();
 
@@ -89,7 +102,7 @@ fn find_range_markers_mt(input: &Vec<char>, range: Range<usize>, n_threads: usiz
         }
     });
     (0..range.len()).map(|i| {
-        results.iter().filter_map(|r| r[i]).min().unwrap()
+        results.iter().filter_map(|r| r[i]).min()
     }).collect()
 }
 
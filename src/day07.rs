@@ -1,121 +1,157 @@
 use std::slice::Iter;
-use std::time::Instant;
-
-const INPUT: &str = include_str!("../input/2022/day07.txt");
-
-fn main() {
-    let lines = INPUT.lines().collect::<Vec<&str>>();
-    //----------------------------------------------------------------------------------------------
-    println!("Non recursive:");
-    let start = std::time::Instant::now();
-
-    let mut lines_iter = lines.iter();
-    lines_iter.next(); //skip first line
-    let mut all_dirs = vec![];
-    let root_dir = parse_dir_non_recursive(&mut lines.iter(), &mut all_dirs);
-
-    let part_1 = all_dirs.iter()
-        .filter(|d| **d <= 100_000).sum::<usize>();
-
-    let space_required = 30_000_000 - (70_000_000 - root_dir);
-    let part_2 = all_dirs.iter()
-        .filter(|d| **d >= space_required)
-        .min().unwrap();
-    println!("Time: {:?}", start.elapsed());
-    println!("Part 1: {}", part_1);
-    println!("Part 2: {}", part_2);
-
-    //----------------------------------------------------------------------------------------------
-    println!();
-    println!("Recursive:");
-    let start = Instant::now();
-
-    let mut all_dirs = vec![];
-    let root_dir = parse_dir(&mut lines_iter, &mut all_dirs);
-
-    let part_1 = all_dirs.iter()
-        .filter(|d| **d <= 100_000).sum::<usize>();
-
-    let space_required = 30_000_000 - (70_000_000 - root_dir);
-    let part_2 = all_dirs.iter()
-        .filter(|d| **d >= space_required)
-        .min().unwrap();
-    println!("Time: {:?}", start.elapsed());
-    println!("Part 1: {}", part_1);
-    println!("Part 2: {}", part_2);
+
+use itertools::Itertools;
+
+use crate::{ParseError, Scanner, Solution};
+use crate::generate::{Generate, Rng};
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = DirSizes;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let lines = input.lines().collect::<Vec<&str>>();
+        let mut all_dirs = vec![];
+        let root_dir = parse_dir_non_recursive(input, &mut lines.iter(), &mut all_dirs)?;
+        Ok(DirSizes { all_dirs, root_dir })
+    }
+
+    fn part1(dir_sizes: &Self::Input) -> usize {
+        dir_sizes.all_dirs.iter()
+            .filter(|d| **d <= 100_000).sum::<usize>()
+    }
+
+    fn part2(dir_sizes: &Self::Input) -> usize {
+        //Deleting the root directory always frees enough
+        let space_required = UPDATE_SIZE.saturating_sub(DISK_SIZE - dir_sizes.root_dir);
+        *dir_sizes.all_dirs.iter()
+            .filter(|d| **d >= space_required)
+            .min().unwrap()
+    }
+}
+
+impl Generate for Day07 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        //Every directory but the root lies in an earlier one
+        let mut children = vec![vec![]; size.max(1)];
+        (1..children.len()).for_each(|dir| {
+            let parent = rng.range(0..=dir - 1);
+            children[parent].push(dir)
+        });
+        let mut files = children.iter().map(|_| vec![0; rng.range(0..=4)]).collect_vec();
+        //The files take less than the 30000000 the update needs, and a big one in the root fills the disk up
+        let max_file = (30_000_000 / files.iter().map(Vec::len).sum::<usize>().max(1)).max(1);
+        files.iter_mut().flatten().for_each(|file| *file = rng.range(1..=max_file));
+        let used = files.iter().flatten().sum::<usize>();
+        files[0].push(rng.range(40_000_000..=69_000_000) - used);
+
+        let mut output = "$ cd /\n".to_string();
+        write_listing(&children, &files, 0, &mut output);
+        output
+    }
 }
 
-pub fn parse_dir(line_iter: &mut Iter<&str>, dirs: &mut Vec<usize>) -> usize {
-    assert_eq!(line_iter.next().unwrap(), &"$ ls"); //ensure the first command is to list the files
+//Terminal output of listing `dir` and then visiting the directories in it
+fn write_listing(children: &[Vec<usize>], files: &[Vec<usize>], dir: usize, output: &mut String) {
+    output.push_str("$ ls\n");
+    children[dir].iter().for_each(|child| output.push_str(&format!("dir d{}\n", child)));
+    files[dir].iter().enumerate().for_each(|(i, size)| output.push_str(&format!("{} f{}.txt\n", size, i)));
+    for &child in &children[dir] {
+        output.push_str(&format!("$ cd d{}\n", child));
+        write_listing(children, files, child, output);
+        output.push_str("$ cd ..\n");
+    }
+}
+
+const DISK_SIZE: usize = 70_000_000;
+const UPDATE_SIZE: usize = 30_000_000;
+
+pub struct DirSizes {
+    all_dirs: Vec<usize>,
+    root_dir: usize,
+}
+
+//A line of the terminal session: a command, or a directory or file listed by ls
+enum Line<'a> {
+    Cd(&'a str),
+    Ls,
+    Dir,
+    File(usize),
+}
+
+//Names of the directories and files are whatever follows on their line
+fn scan_line(line: &str) -> Result<Line<'_>, ParseError> {
+    let mut scan = Scanner::new(line);
+    if scan.eat("$") {
+        return match scan.one_of(&["cd", "ls"])? {
+            "cd" => match scan.rest().trim() {
+                "" => Err(scan.error("a directory")),
+                dir => Ok(Line::Cd(dir)),
+            },
+            _ => scan.end().map(|_| Line::Ls),
+        };
+    }
+    match scan.eat("dir") {
+        true => Ok(Line::Dir),
+        false => scan.integer("a command, directory or file size").map(Line::File),
+    }
+}
+
+pub fn parse_dir(input: &str, line_iter: &mut Iter<&str>, dirs: &mut Vec<usize>) -> Result<usize, ParseError> {
+    match line_iter.next() { //ensure the first command is to list the files
+        Some(&"$ ls") => {}
+        Some(line) => return Err(ParseError::at(input, line, "\"$ ls\"")),
+        None => return Err(ParseError::end(input, "\"$ ls\"")),
+    }
     let mut total_file_size = 0;
-    loop {
-        let line = line_iter.next();
-        if line == None || line.unwrap().is_empty() {
-            break; //go back to parent dir
+    while let Some(line) = line_iter.next().filter(|line| !line.is_empty()) {
+        match scan_line(line).map_err(|e| e.within(input, line))? {
+            Line::File(file_size) => total_file_size += file_size,
+            Line::Dir => continue, //directory detected, ignore
+            Line::Cd("..") => break, //go back to parent dir
+            Line::Cd(_) => total_file_size += parse_dir(input, line_iter, dirs)?, // go into dir
+            Line::Ls => return Err(ParseError::at(input, line, "\"$ cd\"")),
         }
-        let mut line_splitter = line.unwrap().split(' ');
-        let first_wrd = line_splitter.next().unwrap();
-        if let Ok(file_size) = first_wrd.parse::<usize>() {
-            total_file_size += file_size; //file detected
-        } else if first_wrd.starts_with("dir") {
-            continue; //directory detected, ignore
-        } else if first_wrd.chars().nth(0).unwrap() == '$' {
-            match line_splitter.next().unwrap() {
-                "cd" => match line_splitter.next().unwrap() {
-                    ".." => break, //go back to parent dir
-                    _ => total_file_size += parse_dir(line_iter, dirs), // go into dir
-                }
-                _ => panic!("Unexpected command"),
-            }
-        } else { panic!("Unexpected command"); }
     }
     dirs.push(total_file_size);
-    total_file_size
+    Ok(total_file_size)
 }
 
-pub fn parse_dir_non_recursive(line_iter: &mut Iter<&str>, dirs: &mut Vec<usize>) -> usize {
+pub fn parse_dir_non_recursive(input: &str, line_iter: &mut Iter<&str>, dirs: &mut Vec<usize>) -> Result<usize, ParseError> {
     line_iter.next(); //skip first line
     let mut size_buffer = vec![0_usize];
     let mut current_depth : usize = 0;
 
-    loop {
-        let line = line_iter.next();
-        if line == None || line.unwrap().is_empty() {
-            break;
-        }
-        let mut line_splitter = line.unwrap().split(' ');
-        let first_wrd = line_splitter.next().unwrap();
-        if let Ok(file_size) = first_wrd.parse::<usize>() {
-            size_buffer[current_depth] += file_size; //file detected
-        } else if first_wrd.starts_with("dir") {
-            continue; //directory detected, ignore
-        } else if first_wrd.chars().nth(0).unwrap() == '$' {
-            match line_splitter.next().unwrap() {
-                "cd" => match line_splitter.next().unwrap() {
-                    ".." => {
-                        //go back to parent dir
-                        let dir_size = size_buffer.pop().unwrap();
-                        size_buffer[current_depth - 1] += dir_size;
-                        dirs.push(dir_size);
-                        current_depth -= 1;
-                    },
-                    _ =>{
-                        // go into dir
-                        size_buffer.push(0);
-                        current_depth += 1;
-                    }
-                }
-                "ls" => {
-                    continue; //ignore
-                }
-                _ => panic!("Unexpected command: {}", line.unwrap()),
+    while let Some(line) = line_iter.next().filter(|line| !line.is_empty()) {
+        match scan_line(line).map_err(|e| e.within(input, line))? {
+            //Files can only add up to the size of the disk
+            Line::File(file_size) if file_size > DISK_SIZE - size_buffer.iter().sum::<usize>() => {
+                return Err(ParseError::at(input, line, format!("files adding up to at most {}", DISK_SIZE)));
+            }
+            Line::File(file_size) => size_buffer[current_depth] += file_size,
+            Line::Dir | Line::Ls => continue, //ignore
+            Line::Cd("..") if current_depth == 0 => return Err(ParseError::at(input, line, "a directory to leave")),
+            Line::Cd("..") => {
+                //go back to parent dir
+                let dir_size = size_buffer.pop().unwrap();
+                size_buffer[current_depth - 1] += dir_size;
+                dirs.push(dir_size);
+                current_depth -= 1;
+            },
+            Line::Cd(_) => {
+                // go into dir
+                size_buffer.push(0);
+                current_depth += 1;
             }
-        } else { panic!("Unexpected command: {}", line.unwrap()); }
+        }
     }
     //push all remaining dirs by folding the size_buffer in reverse
     size_buffer.iter().rev().fold(0, |acc, s| {
         dirs.push(acc + s);
         acc + s
     });
-    dirs[dirs.len()-1]
+    Ok(dirs[dirs.len()-1])
 }
\ No newline at end of file
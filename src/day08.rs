@@ -1,54 +1,62 @@
 use std::str::FromStr;
 
-const INPUT: &str = include_str!("../input/2022/day08.txt");
+use crate::{Grid, ParseError, Solution};
+use crate::generate::{Generate, Rng};
 
-fn main() {
-    let start = std::time::Instant::now();
-    let tree_grid: TreeGrid = INPUT.parse().unwrap();
+pub struct Day08;
 
-    let visibility_matrix = tree_grid.create_visibility_matrix();
-    let scenic_matrix = tree_grid.create_scenic_score_matrix();
+impl Solution for Day08 {
+    type Input = TreeGrid;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    let n_visible = visibility_matrix.iter().flatten().filter(|v| **v).count();
-    let max_scenic = scenic_matrix.iter().flatten().max().unwrap();
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(tree_grid: &Self::Input) -> usize {
+        let visibility_matrix = tree_grid.create_visibility_matrix();
+        visibility_matrix.iter().filter(|v| **v).count()
+    }
 
-    println!("Time: {:?}", start.elapsed());
-    println!("Part 1: {}", n_visible);
-    println!("Part 2: {}", max_scenic);
+    fn part2(tree_grid: &Self::Input) -> usize {
+        let scenic_matrix = tree_grid.create_scenic_score_matrix();
+        *scenic_matrix.iter().max().unwrap()
+    }
 }
 
+impl Generate for Day08 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let n = size.max(1);
+        (0..n).map(|_| (0..n).map(|_| (b'0' + rng.range(0..=9)) as char).chain(['\n']).collect::<String>()).collect()
+    }
+}
 
-struct TreeGrid {
-    grid: Vec<Vec<u8>>,
+pub struct TreeGrid {
+    grid: Grid<u8>,
 }
 
 impl FromStr for TreeGrid {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut grid = vec![];
-        for line in s.lines() {
-            if !line.is_empty() {
-                let grid_line = line.chars().map(|c| c.to_string().parse::<u8>().unwrap()).collect::<Vec<u8>>();
-                grid.push(grid_line);
-            }
-        }
+        let grid = Grid::parse(s, |c| c.to_digit(10).map(|h| h as u8), "a tree height (0-9)")?;
         Ok(TreeGrid { grid })
     }
 }
 
 impl TreeGrid {
-    fn create_visibility_matrix(&self) -> Vec<Vec<bool>> {
-        let (width, height) = (self.grid[0].len(), self.grid.len());
-        let mut visibility_matrix = vec![vec![false; width]; height];
+    fn create_visibility_matrix(&self) -> Grid<bool> {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let mut visibility_matrix = Grid::new(width, height, false);
 
         //up
         for x in 0..width {
             let mut highest_visible = -1;
             for y in 0..height {
-                let tree_height = self.grid[y][x];
+                let tree_height = self.grid[(x, y)];
                 if tree_height as i32 > highest_visible {
-                    visibility_matrix[y][x] = true;
+                    visibility_matrix[(x, y)] = true;
                     highest_visible = tree_height as i32;
                 }
             }
@@ -58,9 +66,9 @@ impl TreeGrid {
         for y in 0..height {
             let mut highest_visible = -1;
             for x in (0..width).rev() {
-                let tree_height = self.grid[y][x];
+                let tree_height = self.grid[(x, y)];
                 if tree_height as i32 > highest_visible {
-                    visibility_matrix[y][x] = true;
+                    visibility_matrix[(x, y)] = true;
                     highest_visible = tree_height as i32;
                 }
             }
@@ -70,9 +78,9 @@ impl TreeGrid {
         for x in 0..width {
             let mut highest_visible = -1;
             for y in (0..height).rev() {
-                let tree_height = self.grid[y][x];
+                let tree_height = self.grid[(x, y)];
                 if tree_height as i32 > highest_visible {
-                    visibility_matrix[y][x] = true;
+                    visibility_matrix[(x, y)] = true;
                     highest_visible = tree_height as i32;
                 }
             }
@@ -82,9 +90,9 @@ impl TreeGrid {
         for y in 0..height {
             let mut highest_visible = -1;
             for x in 0..width {
-                let tree_height = self.grid[y][x];
+                let tree_height = self.grid[(x, y)];
                 if tree_height as i32 > highest_visible {
-                    visibility_matrix[y][x] = true;
+                    visibility_matrix[(x, y)] = true;
                     highest_visible = tree_height as i32;
                 }
             }
@@ -93,17 +101,17 @@ impl TreeGrid {
         visibility_matrix
     }
 
-    fn create_scenic_score_matrix(&self) -> Vec<Vec<usize>> {
-        let (width, height) = (self.grid[0].len(), self.grid.len());
+    fn create_scenic_score_matrix(&self) -> Grid<usize> {
+        let (width, height) = (self.grid.width(), self.grid.height());
 
-        let mut scenic_scores = vec![vec![1; width]; height];
+        let mut scenic_scores = Grid::new(width, height, 1);
 
         //up
         for x in 0..width {
             let mut height_last_seen = [0; 10];
             for y in 0..height {
-                let tree_height = self.grid[y][x] as usize;
-                scenic_scores[y][x] *= y - height_last_seen[tree_height];
+                let tree_height = self.grid[(x, y)] as usize;
+                scenic_scores[(x, y)] *= y - height_last_seen[tree_height];
                 for i in 0..=tree_height {
                     height_last_seen[i] = y;
                 }
@@ -114,8 +122,8 @@ impl TreeGrid {
         for y in 0..height {
             let mut height_last_seen = [width - 1; 10];
             for x in (0..width).rev() {
-                let tree_height = self.grid[y][x] as usize;
-                scenic_scores[y][x] *= height_last_seen[tree_height] - x;
+                let tree_height = self.grid[(x, y)] as usize;
+                scenic_scores[(x, y)] *= height_last_seen[tree_height] - x;
                 for i in 0..=tree_height {
                     height_last_seen[i] = x;
                 }
@@ -126,8 +134,8 @@ impl TreeGrid {
         for x in 0..width {
             let mut height_last_seen = [height - 1; 10];
             for y in (0..height).rev() {
-                let tree_height = self.grid[y][x] as usize;
-                scenic_scores[y][x] *= height_last_seen[tree_height] - y;
+                let tree_height = self.grid[(x, y)] as usize;
+                scenic_scores[(x, y)] *= height_last_seen[tree_height] - y;
                 for i in 0..=tree_height {
                     height_last_seen[i] = y;
                 }
@@ -138,8 +146,8 @@ impl TreeGrid {
         for y in 0..height {
             let mut height_last_seen = [0; 10];
             for x in 0..width {
-                let tree_height = self.grid[y][x] as usize;
-                scenic_scores[y][x] *= x - height_last_seen[tree_height];
+                let tree_height = self.grid[(x, y)] as usize;
+                scenic_scores[(x, y)] *= x - height_last_seen[tree_height];
                 for i in 0..=tree_height {
                     height_last_seen[i] = x;
                 }
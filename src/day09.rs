@@ -1,79 +1,96 @@
 use std::str::FromStr;
-use std::time::Instant;
+use std::sync::OnceLock;
 use fxhash::FxHashSet;
-use aoc2022::parse_to_vec;
+use crate::{parse_to_vec, Dir4, ParseError, Point2, Scanner, Solution};
+use crate::generate::{Generate, Rng};
 
-const INPUT: &str = include_str!("../input/2022/day09.txt");
+pub struct Day09;
 
-fn main(){
-    let start = Instant::now();
-    let head_actions  = parse_to_vec(INPUT, "\n").unwrap();
-    let mut rope = vec![RopeSegment(0,0); 10];
+impl Solution for Day09 {
+    type Input = Rope;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    let (part_1, part_2) = simulate(&mut rope, &head_actions);
-    let duration = start.elapsed();
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(Rope { head_actions: parse_to_vec(input, "\n")?, visited: OnceLock::new() })
+    }
+
+    fn part1(rope: &Self::Input) -> usize {
+        rope.visited().second
+    }
+
+    fn part2(rope: &Self::Input) -> usize {
+        rope.visited().last
+    }
+}
+
+//Both parts follow the same rope, so it is moved by whichever part runs first and the other one reuses the result
+pub struct Rope {
+    head_actions: Vec<Action>,
+    visited: OnceLock<Visited>,
+}
+
+impl Rope {
+    fn visited(&self) -> &Visited {
+        self.visited.get_or_init(|| simulate(&mut [Point2::ORIGIN; 10], &self.head_actions))
+    }
+}
 
-    println!("Part 1: {}", part_1);
-    println!("Part 2: {}", part_2);
-    println!("Time: {:?}", duration);
+//Number of positions visited by the knot behind the head and by the tail
+struct Visited {
+    second: usize,
+    last: usize,
+}
+
+impl Generate for Day09 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size.max(1)).map(|_| format!("{} {}\n", rng.pick(&["U", "D", "L", "R"]), rng.range(1..=20))).collect()
+    }
 }
 
-fn simulate(rope: &mut [RopeSegment], head_actions: &[Action]) -> (usize,usize) {
+fn simulate(rope: &mut [Point2], head_actions: &[Action]) -> Visited {
     let mut pos_1_set = FxHashSet::default();
     let mut pos_9_set = FxHashSet::default();
     for action in head_actions {
         for _ in 0..action.dist {
-            let mut rope_iter = rope.iter_mut();
-            let mut prev_segment = rope_iter.next().unwrap();
-            prev_segment.move_in_dir(&action.dir);
-            for segment in rope_iter {
-                segment.react(prev_segment);
-                prev_segment = segment;
+            rope[0] += action.dir.offset();
+            for i in 1..rope.len() {
+                rope[i] = react(rope[i], rope[i - 1]);
             }
-            pos_1_set.insert(rope[1].clone());
-            pos_9_set.insert(rope[9].clone());
+            pos_1_set.insert(rope[1]);
+            pos_9_set.insert(rope[9]);
         }
     }
-    (pos_1_set.len(),pos_9_set.len())
+    Visited { second: pos_1_set.len(), last: pos_9_set.len() }
 }
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
-struct RopeSegment(i32,i32);
-
-impl RopeSegment {
-    fn move_in_dir(&mut self, dir: &(i32,i32)){
-        self.0 += dir.0;
-        self.1 += dir.1;
-    }
-
-    fn react(&mut self, prev: &RopeSegment){
-        let (dx,dy) = ((prev.0 - self.0), (prev.1 - self.1));
-        if dx.abs() > 1 || dy.abs() > 1 {
-            //not (diagonally) adjacent or overlapping, segment needs to react
-            self.0 += dx.signum();
-            self.1 += dy.signum();
-        }
+//Where a segment ends up after the segment in front of it moved to `prev`
+fn react(segment: Point2, prev: Point2) -> Point2 {
+    match segment.chebyshev(prev) > 1 {
+        true => segment + (prev - segment).signum(), //not (diagonally) adjacent or overlapping, segment needs to react
+        false => segment,
     }
 }
 
-struct Action {
-    dir: (i32,i32),
+#[derive(Debug)]
+pub struct Action {
+    dir: Dir4,
     dist: usize,
 }
 
 impl FromStr for Action {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut split = s.split(' ');
-        let dir = match split.next().unwrap() {
-            "U" => (0,1),
-            "D" => (0,-1),
-            "L" => (-1,0),
-            "R" => (1,0),
-            _ => return Err(())
+        let mut scan = Scanner::new(s);
+        let dir = match scan.one_of(&["U", "D", "L", "R"])? {
+            "U" => Dir4::Up,
+            "D" => Dir4::Down,
+            "L" => Dir4::Left,
+            _ => Dir4::Right,
         };
-        let dist = split.next().unwrap().parse::<usize>().unwrap();
+        let dist = scan.integer("a distance")?;
+        scan.end()?;
         Ok(Action {dir, dist})
     }
 }
\ No newline at end of file
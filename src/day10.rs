@@ -1,31 +1,78 @@
 use std::str::FromStr;
 
-use aoc2022::parse_to_vec;
+use crate::{parse_to_vec, ParseError, Scanner, Solution};
+use crate::generate::{Generate, Rng};
 
-const INPUT: &str = include_str!("../input/2022/day10.txt");
+pub struct Day10;
 
-pub fn main() {
-    let instructions = parse_to_vec::<Op>(INPUT, "\n").unwrap();
+impl Solution for Day10 {
+    type Input = Vec<Op>;
+    type Part1 = i32;
+    type Part2 = String;
 
-    let mut cpu = CPU::default();
-    let x_history = cpu.run(&instructions);
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let ops = parse_to_vec::<Op>(input, "\n")?;
+        let n_cycles = ops.iter().map(|op| match op {
+            Op::Noop => 1,
+            Op::Addx(_) => 2,
+        }).sum::<usize>();
+        let last_signal = SIGNAL_CYCLES[SIGNAL_CYCLES.len() - 1];
+        match n_cycles < last_signal {
+            true => Err(ParseError::end(input, format!("instructions lasting {} cycles", last_signal))),
+            false => Ok(ops),
+        }
+    }
+
+    fn part1(instructions: &Self::Input) -> i32 {
+        let mut cpu = CPU::default();
+        let x_history = cpu.run(instructions);
+
+        SIGNAL_CYCLES.iter().map(|i| x_history[*i] * (*i as i32)).sum::<i32>()
+    }
 
-    println!("Part 1: {}", [20, 60, 100, 140, 180, 220].iter().map(|i| x_history[*i] * (*i as i32)).sum::<i32>());
+    fn part2(instructions: &Self::Input) -> String {
+        let mut cpu = CPU::default();
+        let x_history = cpu.run(instructions);
 
-    println!("Part 2:");
-    let x_vec_trimmed = x_history.iter().skip(1).collect::<Vec<&i32>>();
-    x_vec_trimmed.chunks(40).for_each(
-        |row| {
-            for (pixel, &x) in row.iter().enumerate() {
-                if [x - 1, *x, x + 1].contains(&(pixel as i32)) {
-                    print!("██");
-                } else {
-                    print!("░░");
+        let x_vec_trimmed = x_history.iter().skip(1).collect::<Vec<&i32>>();
+        x_vec_trimmed.chunks(40).map(
+            |row| {
+                row.iter().enumerate().map(|(pixel, &x)| {
+                    if [x - 1, *x, x + 1].contains(&(pixel as i32)) {
+                        "██"
+                    } else {
+                        "░░"
+                    }
+                }).collect::<String>()
+            }
+        ).collect::<Vec<String>>().join("\n")
+    }
+}
+
+//Cycles during which part 1 reads the signal strength
+const SIGNAL_CYCLES: [usize; 6] = [20, 60, 100, 140, 180, 220];
+
+impl Generate for Day10 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        //The program lasts for the 240 cycles of drawing the screen, with the sprite staying on the screen
+        let (mut program, mut n_cycles, mut n_ops, mut x) = (String::new(), 0, 0, 1);
+        while n_cycles < 240 || n_ops < size {
+            let v = rng.range(0..=39) - x;
+            match v == 0 || rng.chance(0.3) {
+                true => {
+                    program.push_str("noop\n");
+                    n_cycles += 1;
+                }
+                false => {
+                    program.push_str(&format!("addx {}\n", v));
+                    n_cycles += 2;
+                    x += v;
                 }
             }
-            println!();
+            n_ops += 1;
         }
-    )
+        program
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -46,24 +93,26 @@ pub enum CPUState {
 }
 
 impl FromStr for Op {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(' ');
-        let instruction = parts.next().unwrap();
-        match instruction {
-            "noop" => Ok(Op::Noop),
-            "addx" => Ok(Op::Addx(parts.next().unwrap().parse::<i32>().unwrap())),
-            _ => Err(()),
-        }
+        let mut scan = Scanner::new(s);
+        let op = match scan.one_of(&["noop", "addx"])? {
+            "noop" => Op::Noop,
+            _ => Op::Addx(scan.integer("a number")?),
+        };
+        scan.end()?;
+        Ok(op)
     }
 }
 
-impl CPU {
-    pub fn default() -> Self {
+impl Default for CPU {
+    fn default() -> Self {
         CPU { x: 1, cycle: 0, state: CPUState::Executing(Op::Noop, 0) }
     }
+}
 
+impl CPU {
     pub fn run(&mut self, ops: &[Op]) -> Vec<i32> {
         let mut op_iter = ops.iter();
         let mut x_history = vec![];
@@ -1,43 +1,74 @@
+use std::ops::RangeBounds;
 use std::rc::Rc;
-use std::str::FromStr;
-use std::time::Instant;
 
 use num::integer::lcm;
 
-use aoc2022::parse_to_vec;
+use crate::{sections, ParseError, Scanner, Solution};
+use crate::generate::{Generate, Rng};
 
-const INPUT: &str = include_str!("../input/2022/day11.txt");
+pub struct Day11;
 
-fn main() {
-    let start = Instant::now();
-    let mut monkeys_1: Vec<Monkey> = parse_to_vec(INPUT, "\n\n").unwrap();
-    let mut monkeys_2 = monkeys_1.clone();
-    //least common multiple of all the monkey's divisors
-    let lcm = monkeys_1.iter().fold(1, |acc, m| lcm(acc, m.divisor));
+impl Solution for Day11 {
+    type Input = Vec<Monkey>;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    for _ in 0..20 {
-        for j in 0..monkeys_1.len() {
-            monkeys_1[j].inspect_and_throw(lcm, 3).iter()
-                .for_each(|(item, m_index)| monkeys_1[*m_index].receive(*item));
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let notes = sections(input);
+        if notes.len() < 2 {
+            return Err(ParseError::end(input, "the notes of at least two monkeys"));
         }
+        notes.iter()
+            .map(|note| scan_monkey(note, notes.len()).map_err(|e| e.within(input, note)))
+            .collect()
     }
-    monkeys_1.sort_by(|a, b| a.n_inspections.cmp(&b.n_inspections).reverse());
-    println!("Part 1: {}", monkeys_1[0].n_inspections * monkeys_1[1].n_inspections);
 
-    for _ in 0..10000 {
-        for j in 0..monkeys_2.len() {
-            monkeys_2[j].inspect_and_throw(lcm, 1).iter()
-                .for_each(|(item, m_index)| monkeys_2[*m_index].receive(*item));
-        }
+    fn part1(monkeys: &Self::Input) -> usize {
+        monkey_business(&mut monkeys.clone(), 20, 3)
+    }
+
+    fn part2(monkeys: &Self::Input) -> usize {
+        monkey_business(&mut monkeys.clone(), 10000, 1)
+    }
+}
+
+impl Generate for Day11 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        const DIVISORS: [usize; 9] = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+        let n_monkeys = size.max(2);
+        let notes = (0..n_monkeys).map(|i| {
+            let items = (0..rng.range(1..=6)).map(|_| rng.range(50..=99).to_string()).collect::<Vec<String>>();
+            let operation = match rng.range(0..=4) {
+                0 => "old * old".to_string(),
+                1 | 2 => format!("old * {}", rng.range(2..=19)),
+                _ => format!("old + {}", rng.range(1..=8)),
+            };
+            let divisor = rng.pick(&DIVISORS);
+            //Monkeys never throw to themselves
+            let (if_true, if_false) = ((i + rng.range(1..=n_monkeys - 1)) % n_monkeys, (i + rng.range(1..=n_monkeys - 1)) % n_monkeys);
+            format!("Monkey {}:\n  Starting items: {}\n  Operation: new = {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+                    i, items.join(", "), operation, divisor, if_true, if_false)
+        }).collect::<Vec<String>>();
+        notes.join("\n")
     }
-    monkeys_2.sort_by(|a, b| a.n_inspections.cmp(&b.n_inspections).reverse());
-    println!("Part 2: {}", monkeys_2[0].n_inspections * monkeys_2[1].n_inspections);
+}
+
+fn monkey_business(monkeys: &mut [Monkey], rounds: usize, divider: usize) -> usize {
+    //least common multiple of all the monkey's divisors
+    let lcm = monkeys.iter().fold(1, |acc, m| lcm(acc, m.divisor));
 
-    println!("Time: {:?}", start.elapsed());
+    for _ in 0..rounds {
+        for j in 0..monkeys.len() {
+            monkeys[j].inspect_and_throw(lcm, divider).iter()
+                .for_each(|(item, m_index)| monkeys[*m_index].receive(*item));
+        }
+    }
+    monkeys.sort_by(|a, b| a.n_inspections.cmp(&b.n_inspections).reverse());
+    monkeys[0].n_inspections * monkeys[1].n_inspections
 }
 
 #[derive(Clone)]
-struct Monkey {
+pub struct Monkey {
     pub inventory: Vec<usize>,
     pub worry_op: Rc<dyn Fn(usize) -> usize>, //Rc instead of Box so we can clone Monkey
     pub throw_to: Rc<dyn Fn(usize) -> usize>,
@@ -60,48 +91,74 @@ impl Monkey {
     }
 }
 
-impl FromStr for Monkey {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines();
-        lines.next();
-        let inventory_line = lines.next().unwrap().split(':').nth(1).unwrap();
-        let inventory: Vec<usize> = inventory_line.split(',').filter_map(|x| x.trim().parse().ok()).collect();
-        let op_str = lines.next().unwrap().split(':').nth(1).unwrap();
-        let operation_symbol = op_str.split(' ').nth(4).unwrap();
-        let operand = op_str.split(' ').nth(5).unwrap();
-
-        let worry_op: Rc<dyn Fn(usize) -> usize> = match operand {
-            "old" => {
-                match operation_symbol {
-                    "*" => Rc::new(|x| x * x),
-                    "+" => Rc::new(|x| x + x),
-                    _ => { panic!("Unknown operation_symbol {}", operation_symbol) }
-                }
-            }
-            number => {
-                let number: usize = number.parse().unwrap();
-                match operation_symbol {
-                    "*" => Rc::new(move |x| x * number),
-                    "+" => Rc::new(move |x| x + number),
-                    _ => { panic!("Unknown operation_symbol {}", operation_symbol) }
-                }
+//Notes of one of `n_monkeys` monkeys, which only throw to each other
+fn scan_monkey(s: &str, n_monkeys: usize) -> Result<Monkey, ParseError> {
+    let mut scan = Scanner::new(s);
+    scan.literal("Monkey")?;
+    scan.integer::<usize>("a monkey number")?;
+    scan.literal(":")?;
+    scan.literal("Starting items:")?;
+    let inventory = scan.separated(",", |scan| scan.integer("a worry level"))?;
+
+    scan.literal("Operation: new = old")?;
+    let operation_symbol = scan.one_of(&["*", "+"])?;
+    let worry_op: Rc<dyn Fn(usize) -> usize> = match (operation_symbol, scan.eat("old")) {
+        ("*", true) => Rc::new(|x| x * x),
+        (_, true) => Rc::new(|x| x + x),
+        (operation_symbol, false) => {
+            let number: usize = scan.integer("a number or old")?;
+            match operation_symbol {
+                "*" => Rc::new(move |x| x * number),
+                _ => Rc::new(move |x| x + number),
             }
-        };
+        }
+    };
+
+    scan.literal("Test: divisible by")?;
+    let divisor = bounded(&mut scan, 1.., "a divisor above 0")?;
+    scan.literal("If true: throw to monkey")?;
+    let throw_if_true = bounded(&mut scan, 0..n_monkeys, &format!("one of the {} monkeys", n_monkeys))?;
+    scan.literal("If false: throw to monkey")?;
+    let throw_if_false = bounded(&mut scan, 0..n_monkeys, &format!("one of the {} monkeys", n_monkeys))?;
+    scan.end()?;
+
+    let throw_to = Rc::new(move |x| if x % divisor == 0 { throw_if_true } else { throw_if_false });
+
+    Ok(Monkey {
+        inventory,
+        worry_op,
+        throw_to,
+        divisor,
+        n_inspections: 0,
+    })
+}
 
-        let divisor: usize = lines.next().unwrap().trim().split(' ').nth(3).unwrap().parse().unwrap();
-        let throw_if_true: usize = lines.next().unwrap().trim().split(' ').nth(5).unwrap().parse().unwrap();
-        let throw_if_false: usize = lines.next().unwrap().trim().split(' ').nth(5).unwrap().parse().unwrap();
+//Next integer, which has to lie in `range`
+fn bounded(scan: &mut Scanner, range: impl RangeBounds<usize>, expected: &str) -> Result<usize, ParseError> {
+    let start = *scan;
+    match scan.integer(expected)? {
+        n if range.contains(&n) => Ok(n),
+        _ => Err(start.error(expected)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Solution;
+    use super::Day11;
 
-        let throw_to = Rc::new(move |x| if x % divisor == 0 { throw_if_true } else { throw_if_false });
+    const MONKEY: &str = "Monkey 0:\n  Starting items: 79, 98\n  Operation: new = old * 19\n  Test: divisible by 23\n    If true: throw to monkey 1\n    If false: throw to monkey 0\n";
 
-        Ok(Monkey {
-            inventory,
-            worry_op,
-            throw_to,
-            divisor,
-            n_inspections: 0,
-        })
+    #[test]
+    fn notes_that_would_stop_the_monkeys_are_rejected() {
+        let two = format!("{}\n{}", MONKEY, MONKEY.replace("Monkey 0", "Monkey 1"));
+        assert!(Day11::parse(&two).is_ok());
+
+        let err = Day11::parse(&two.replacen("by 23", "by 0", 1)).err().unwrap();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (4, 22, "a divisor above 0"));
+        let err = Day11::parse(&two.replacen("monkey 1", "monkey 2", 1)).err().unwrap();
+        assert_eq!((err.line, err.found.as_str(), err.expected.as_str()), (5, "2", "one of the 2 monkeys"));
+        assert!(Day11::parse(MONKEY).is_err());
+        assert!(Day11::parse("").is_err());
     }
-}
\ No newline at end of file
+}
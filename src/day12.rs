@@ -1,93 +1,113 @@
-use std::cmp::Reverse;
-use std::collections::BinaryHeap;
-use std::time::Instant;
+use crate::{Grid, ParseError, Solution};
+use crate::search::{self, Neighbors};
+use crate::generate::{Generate, Rng};
 
-const INPUT: &str = include_str!("../input/2022/day12.txt");
+pub struct Day12;
 
-fn main() {
-    let start = Instant::now();
-    let char_matrix = INPUT.lines().map(|line| line.chars().collect::<Vec<char>>()).collect::<Vec<Vec<char>>>();
-    let mut graph = Graph::new(&char_matrix);
-    println!("Part 1: {}", graph.dijkstra());
-    //Add a virtual start node and edges to all possible start locations
-    let virtual_start = 0;
-    let virtual_edges = graph.node_heights.iter().enumerate()
-        .filter(|(_, height)| **height == 0)
-        .map(|(index, _)| index).collect::<Vec<usize>>();
-    graph.start = graph.node_heights.len();
-    graph.node_heights.push(virtual_start);
-    graph.edges.push(virtual_edges);
-    println!("Part 2: {}", graph.dijkstra() - 1); // -1 to account for the virtual start node
-    println!("Time: {:?}", start.elapsed());
+impl Solution for Day12 {
+    type Input = Graph;
+    type Part1 = Result<usize, ParseError>;
+    type Part2 = Result<usize, ParseError>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let char_grid = Grid::parse(input, |c| matches!(c, 'a'..='z' | 'S' | 'E').then_some(c), "a height (a-z, S or E)")?;
+        Graph::new(input, &char_grid)
+    }
+
+    fn part1(graph: &Self::Input) -> Result<usize, ParseError> {
+        graph.shortest_path([graph.start])
+    }
+
+    fn part2(graph: &Self::Input) -> Result<usize, ParseError> {
+        //Search from all possible start locations at once
+        let starts = graph.node_heights.iter().enumerate()
+            .filter(|(_, height)| **height == 0)
+            .map(|(index, _)| index);
+        graph.shortest_path(starts)
+    }
 }
 
-struct Graph {
+impl Generate for Day12 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        //Heights rise evenly from S in the top left corner to E in the bottom right one, apart from random dips
+        let n = size.max(14);
+        let rise = |x: usize, y: usize| ((x + y) * 25 / (2 * n - 2)) as u8;
+        //A staircase from S to E stays clear of dips, so E can always be reached
+        let mut staircase = Grid::new(n, n, false);
+        let (mut x, mut y) = (0, 0);
+        staircase[(x, y)] = true;
+        while (x, y) != (n - 1, n - 1) {
+            match y == n - 1 || (x < n - 1 && rng.chance(0.5)) {
+                true => x += 1,
+                false => y += 1,
+            }
+            staircase[(x, y)] = true;
+        }
+        (0..n).map(|y| (0..n).map(|x| match (x, y) {
+            (0, 0) => 'S',
+            _ if (x, y) == (n - 1, n - 1) => 'E',
+            _ if staircase[(x, y)] => (b'a' + rise(x, y)) as char,
+            _ => (b'a' + rise(x, y).saturating_sub(rng.range(0..=3))) as char,
+        }).chain(['\n']).collect::<String>()).collect()
+    }
+}
+
+#[derive(Clone)]
+pub struct Graph {
     start: usize,
     end: usize,
     node_heights: Vec<usize>,
     edges: Vec<Vec<usize>>,
+    //Reported by the parts when no path climbs up to E
+    unreachable_end: ParseError,
 }
 
 impl Graph {
-    fn new(char_matrix: &Vec<Vec<char>>) -> Self {
-        let mut node_heights: Vec<usize> = vec![];
-        let (mut start, mut end) = (0, 0);
-        let grid_width = char_matrix[0].len();
-        for row in char_matrix {
-            for c in row {
-                let height = match c {
-                    'S' => 0,
-                    'E' => 25,
-                    c => *c as usize - 'a' as usize
-                };
-                match c {
-                    'S' => start = node_heights.len(),
-                    'E' => end = node_heights.len(),
-                    _ => {}
-                }
-                node_heights.push(height);
-            }
-        }
-        let edges: Vec<Vec<usize>> = (0..node_heights.len()).map(|i| {
-            let mut neighbors = vec![];
-            if i % grid_width != 0 {
-                neighbors.push(i - 1); //left
-            }
-            if i % grid_width != grid_width - 1 {
-                neighbors.push(i + 1); //right
-            }
-            if i >= grid_width {
-                neighbors.push(i - grid_width);  //up
-            }
-            if i < node_heights.len() - grid_width {
-                neighbors.push(i + grid_width); //down
-            }
-            neighbors.into_iter()
-                .filter(|&n| node_heights[i] + 1 >= node_heights[n]) //reachable
+    fn new(input: &str, char_grid: &Grid<char>) -> Result<Self, ParseError> {
+        let heights = char_grid.map(|c| match c {
+            'S' => 0,
+            'E' => 25,
+            c => *c as usize - 'a' as usize
+        });
+        //Nodes are numbered in the order of the cells of the grid
+        let node = |(x, y): (usize, usize)| y * char_grid.width() + x;
+        let start = node(char_grid.position(|c| *c == 'S').ok_or_else(|| ParseError::end(input, "a start S"))?);
+        let end = node(char_grid.position(|c| *c == 'E').ok_or_else(|| ParseError::end(input, "an end E"))?);
+        let e = input.find('E').unwrap();
+        let unreachable_end = ParseError::at(input, &input[e..e + 1], "an end E that can be climbed to");
+        let edges: Vec<Vec<usize>> = heights.positions().map(|(x, y)| {
+            heights.neighbors4(x, y)
+                .filter(|&n| heights[(x, y)] + 1 >= heights[n]) //reachable
+                .map(node)
                 .collect()
         }).collect();
 
-        Graph { start, end, node_heights, edges }
+        Ok(Graph { start, end, node_heights: heights.iter().copied().collect(), edges, unreachable_end })
     }
 
-    fn dijkstra(&self) -> usize {
-        let mut distances = vec![usize::MAX; self.node_heights.len()];
-        distances[self.start] = 0;
-        let mut queue = BinaryHeap::new();
-        queue.push(Reverse((0, self.start))); //Reverse to make it a min heap
+    fn shortest_path(&self, starts: impl IntoIterator<Item = usize>) -> Result<usize, ParseError> {
+        search::bfs(self, starts, |&node| node == self.end).map(|path| path.cost).ok_or_else(|| self.unreachable_end.clone())
+    }
+}
 
-        while let Some(Reverse((distance, node))) = queue.pop() {
-            if node == self.end {
-                break;
-            }
-            for to in &self.edges[node] {
-                let new_distance = distance + 1;
-                if new_distance < distances[*to] {
-                    distances[*to] = new_distance;
-                    queue.push(Reverse((new_distance, *to)));
-                }
-            }
-        }
-        distances[self.end]
+impl Neighbors<usize> for Graph {
+    fn neighbors(&self, node: &usize) -> impl Iterator<Item = (usize, usize)> {
+        self.edges[*node].iter().map(|&to| (to, 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solution::solve;
+    use super::Day12;
+
+    #[test]
+    fn maps_without_a_way_up_to_e_have_no_answer() {
+        let err = solve::<Day12>("Sab\nabc\n", None).unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (3, 1, "an end E"));
+        let err = solve::<Day12>("Sbz\nbzE\n", None).unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (2, 3, "an end E that can be climbed to"));
+        let (answers, _) = solve::<Day12>("SbcdefghijklmnopqrstuvwxyzE\n", None).unwrap();
+        assert_eq!((answers.part_1.as_deref(), answers.part_2.as_deref()), (Some("26"), Some("26")));
     }
 }
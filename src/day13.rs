@@ -1,42 +1,82 @@
-extern crate core;
-
 use std::cmp::Ordering;
 use std::str::FromStr;
 use itertools::Itertools;
 
 use serde_json::value::Value;
 
-const INPUT: &str = include_str!("../input/2022/day13.txt");
-
-fn main() {
-    let packets = INPUT.replace("\n\n", "\n").lines()
-        .map(|l| l.parse().unwrap())
-        .collect::<Vec<Packet>>();
-    let part_1 = (1..=(packets.len() / 2)).zip(packets.iter().tuples().collect::<Vec<(&Packet, &Packet)>>())
-        .filter(|(_, (p1, p2))| packet_pair_right_order(p1, p2).unwrap())
-        .fold(0, |acc, (i, _)| acc + i);
-
-    println!("Part 1: {}", part_1);
-
-    let distress = (
-        Packet { data: vec![Element::Vec(vec![Element::Data(2)])] },
-        Packet { data: vec![Element::Vec(vec![Element::Data(6)])] }
-    );
-
-    let sorted_packets = packets.iter()
-        .chain([&distress.0, &distress.1])
-        .sorted_by(|p1, p2| {
-            match packet_pair_right_order(p1, p2) {
-                Some(true) => Ordering::Less,
-                Some(false) => Ordering::Greater,
-                None => Ordering::Equal,
+use crate::{ParseError, Solution};
+use crate::generate::{Generate, Rng};
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Vec<Packet>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(packets: &Self::Input) -> usize {
+        let part_1 = (1..=(packets.len() / 2)).zip(packets.iter().tuples().collect::<Vec<(&Packet, &Packet)>>())
+            .filter(|(_, (p1, p2))| packet_pair_right_order(p1, p2).unwrap())
+            .fold(0, |acc, (i, _)| acc + i);
+
+        part_1
+    }
+
+    fn part2(packets: &Self::Input) -> usize {
+        let distress = (
+            Packet { data: vec![Element::Vec(vec![Element::Data(2)])] },
+            Packet { data: vec![Element::Vec(vec![Element::Data(6)])] }
+        );
+
+        let sorted_packets = packets.iter()
+            .chain([&distress.0, &distress.1])
+            .sorted_by(|p1, p2| {
+                match packet_pair_right_order(p1, p2) {
+                    Some(true) => Ordering::Less,
+                    Some(false) => Ordering::Greater,
+                    None => Ordering::Equal,
+                }
+            }).collect::<Vec<&Packet>>();
+
+        let part_2 = (sorted_packets.iter().position(|p| std::ptr::eq(*p, &distress.0)).unwrap() + 1) *
+            (sorted_packets.iter().position(|p| std::ptr::eq(*p, &distress.1)).unwrap() + 1);
+
+        part_2
+    }
+}
+
+impl Generate for Day13 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let mut pairs = vec![];
+        while pairs.len() < size.max(1) {
+            let (left, right) = (random_list(rng, 0), random_list(rng, 0));
+            //Every pair has to be in some order
+            let packets = [&left, &right].map(|p| p.parse::<Packet>().expect("generated packets are valid"));
+            if packet_pair_right_order(&packets[0], &packets[1]).is_some() {
+                pairs.push(format!("{}\n{}\n", left, right));
             }
-        }).collect::<Vec<&Packet>>();
+        }
+        pairs.join("\n")
+    }
+}
 
-    let part_2 = (sorted_packets.iter().position(|p| std::ptr::eq(*p, &distress.0)).unwrap() + 1) *
-        (sorted_packets.iter().position(|p| std::ptr::eq(*p, &distress.1)).unwrap() + 1);
+//List of integers and lists, nested up to three levels deep
+fn random_list(rng: &mut Rng, depth: usize) -> String {
+    let items = (0..rng.range(0..=4)).map(|_| match depth < 3 && rng.chance(0.3) {
+        true => random_list(rng, depth + 1),
+        false => rng.range(0..=10).to_string(),
+    }).join(",");
+    format!("[{}]", items)
+}
 
-    println!("Part 2: {}", part_2);
+fn parse(input: &str) -> Result<Vec<Packet>, ParseError> {
+    input.lines().filter(|l| !l.is_empty())
+        .map(|l| l.parse::<Packet>().map_err(|e| e.within(input, l)))
+        .collect::<Result<Vec<Packet>, ParseError>>()
 }
 
 fn packet_pair_right_order(p1: &Packet, p2: &Packet) -> Option<bool> {
@@ -69,36 +109,39 @@ fn elements_right_order(e1: &Element, e2: &Element) -> Option<bool> {
 }
 
 #[derive(Debug, Clone)]
-struct Packet {
+pub struct Packet {
     pub data: Vec<Element>,
 }
 
 #[derive(Debug, Clone)]
-enum Element {
+pub enum Element {
     Data(usize),
     Vec(Vec<Element>),
 }
 
 impl FromStr for Packet {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const EXPECTED: &str = "a packet of nested lists and integers";
         match serde_json::from_str(s) {
             Ok(Value::Array(v)) => {
-                let data = v.iter().map(Element::from_json).collect::<Vec<Element>>();
+                let data = v.iter().map(Element::from_json).collect::<Option<Vec<Element>>>()
+                    .ok_or_else(|| ParseError::at(s, s, EXPECTED))?;
                 Ok(Packet { data })
             }
-            _ => Err(()),
+            Ok(_) => Err(ParseError::at(s, s, EXPECTED)),
+            Err(e) => Err(ParseError::at(s, s.get(e.column().saturating_sub(1)..).unwrap_or(&s[s.len()..]), EXPECTED)),
         }
     }
 }
 
 impl Element {
-    fn from_json(val: &Value) -> Element {
+    fn from_json(val: &Value) -> Option<Element> {
         match val {
-            Value::Array(v) => Element::Vec(v.iter().map(Element::from_json).collect::<Vec<Element>>()),
-            Value::Number(v) => Element::Data(v.as_u64().unwrap() as usize),
-            _ => panic!("unexpected json value")
+            Value::Array(v) => Some(Element::Vec(v.iter().map(Element::from_json).collect::<Option<Vec<Element>>>()?)),
+            Value::Number(v) => Some(Element::Data(v.as_u64()? as usize)),
+            _ => None
         }
     }
 }
\ No newline at end of file
@@ -1,31 +1,83 @@
 use std::str::FromStr;
-use std::time::Instant;
 
-const INPUT: &str = include_str!("../input/2022/day14.txt");
+use crate::{Dir4, Grid, ParseError, Point2, Scanner, Solution};
+use crate::visualize::{Frame, Recorder, Visualize};
+use crate::generate::{Generate, Rng};
 
-fn main() {
-    let start = Instant::now();
-    let mut cave : Cave = INPUT.parse().unwrap();
-    let part1 = cave.simulate();
-    println!("Part 1: {}", part1);
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Cave;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(cave: &Self::Input) -> usize {
+        cave.clone().simulate(&mut Recorder::off())
+    }
+
+    fn part2(cave: &Self::Input) -> usize {
+        fill_to_source(cave, &mut Recorder::off())
+    }
+}
+
+impl Generate for Day14 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        //Paths of rock below the source, going sideways and down in turns
+        (0..size.max(1)).map(|_| {
+            let mut point = Point2::new(rng.range(480..=520), rng.range(5..=5 + 2 * size as i32));
+            let mut path = vec![point];
+            for i in 0..rng.range(1..=4) {
+                point += match i % 2 {
+                    0 => Point2::new(rng.range(-6..=6), 0),
+                    _ => Point2::new(0, rng.range(1..=6)),
+                };
+                path.push(point);
+            }
+            path.iter().map(|p| format!("{},{}", p.x, p.y)).collect::<Vec<String>>().join(" -> ") + "\n"
+        }).collect()
+    }
+}
+
+impl Visualize for Day14 {
+    fn visualize(cave: &Self::Input, part: u8, recorder: &mut Recorder) {
+        match part {
+            1 => { cave.clone().simulate(recorder); },
+            _ => { fill_to_source(cave, recorder); },
+        }
+    }
+}
+
+//Sand resting once the floor below the cave is added, until the source is covered
+fn fill_to_source(cave: &Cave, recorder: &mut Recorder) -> usize {
+    let mut cave = cave.clone();
+    let part1 = cave.simulate(recorder);
     cave.add_bedrock();
-    println!("Part 2: {}", part1 + cave.simulate());
-    println!("Time: {:?}", start.elapsed());
+    part1 + cave.simulate(recorder)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Air,
+    Rock,
+    Sand,
 }
 
 #[derive(Debug, Clone)]
-struct Cave {
-    start: (i32, i32),
-    shape: (i32, i32),
-    grid: Vec<Vec<bool>>,
+pub struct Cave {
+    start: Point2,
+    grid: Grid<Tile>,
 }
 
 impl Cave {
-    fn simulate(&mut self) -> usize {
+    fn simulate(&mut self, recorder: &mut Recorder) -> usize {
         let mut sand_counter = 0;
-        let left = |pos: (i32, i32)| { (pos.0 - 1, pos.1) };
-        let right = |pos: (i32, i32)| { (pos.0 + 1, pos.1) };
-        let under = |pos: (i32, i32)| { (pos.0, pos.1 + 1) };
+        let left = |pos: Point2| pos + Dir4::Left.offset();
+        let right = |pos: Point2| pos + Dir4::Right.offset();
+        let under = |pos: Point2| pos + Dir4::Down.offset();
 
         loop {
             let mut curr = self.start;
@@ -39,8 +91,9 @@ impl Cave {
                         curr = right(curr);
                     } else {
                         //resting place reached
-                        self.grid[curr.0 as usize][curr.1 as usize] = true;
+                        self.grid[curr] = Tile::Sand;
                         sand_counter += 1;
+                        recorder.frame(|| self.frame(sand_counter));
                         break;
                     }
                 } else {
@@ -54,85 +107,105 @@ impl Cave {
         sand_counter
     }
 
-    fn is_supported(&self, mut pos: (i32, i32)) -> bool {
-        pos = (pos.0, pos.1 + 1);
-        match self.out_of_bounds(pos) {
-            true => false,
-            false => self.grid[pos.0 as usize][pos.1 as usize],
-        }
+    fn is_supported(&self, pos: Point2) -> bool {
+        self.grid.get(pos.x, pos.y + 1).is_some_and(|&tile| tile != Tile::Air)
     }
 
-    fn out_of_bounds(&self, pos: (i32, i32)) -> bool {
-        pos.0 < 0 || pos.1 < 0 || pos.0 >= self.shape.0 as i32 || pos.1 >= self.shape.1 as i32
+    fn out_of_bounds(&self, pos: Point2) -> bool {
+        !self.grid.contains(pos.x, pos.y)
     }
 
     fn start_covered(&self) -> bool {
-        self.grid[self.start.0 as usize][self.start.1 as usize]
+        self.grid[self.start] != Tile::Air
     }
 
     fn add_bedrock(&mut self) {
-        //expand the grid to accommodate the bedrock
-        let bedrock_level = self.shape.1 + 1;
-        let max_width = 4 * bedrock_level; //to be safe
-        for column in self.grid.iter_mut() {
-            column.extend(vec![false, true]);
-        }
-        let mut inserts_front = 0;
-        let mut inserts_back = 0;
-        while self.grid.len() < max_width as usize {
-            let mut new_column = vec![false; bedrock_level as usize];
-            new_column.push(true);
-            match inserts_front <= inserts_back {
-                true => {
-                    inserts_front += 1;
-                    self.grid.insert(0, new_column)
-                },
-                false => {
-                    inserts_back += 1;
-                    self.grid.push(new_column)
-                },
-            }
-        }
-        self.shape = (max_width, bedrock_level + 1);
-        self.start.0 += inserts_front;
+        //expand the grid to accommodate the bedrock, adding columns on both sides
+        let bedrock_level = self.grid.height() + 1;
+        let width = self.grid.width().max(4 * bedrock_level); //to be safe
+        let inserts_front = (width - self.grid.width()).div_ceil(2);
+        self.grid = Grid::from_fn(width, bedrock_level + 1, |x, y| match (y == bedrock_level, x >= inserts_front) {
+            (true, _) => Tile::Rock,
+            (false, true) => self.grid.get((x - inserts_front) as i32, y as i32).copied().unwrap_or(Tile::Air),
+            (false, false) => Tile::Air,
+        });
+        self.start.x += inserts_front as i32;
+    }
+
+    //The columns of the cave holding anything but air, with the source of the sand on top
+    fn frame(&self, sand_counter: usize) -> Frame {
+        let used = |x: usize| x == self.start.x as usize || self.grid.column(x).any(|&tile| tile != Tile::Air);
+        let (left, right) = ((0..self.grid.width()).find(|&x| used(x)), (0..self.grid.width()).rfind(|&x| used(x)));
+        let columns = left.unwrap_or(0)..right.map_or(0, |x| x + 1);
+        let picture = self.grid.rows().enumerate().map(|(y, row)| {
+            row[columns.clone()].iter().enumerate().map(|(x, tile)| match tile {
+                Tile::Air if (x + columns.start, y) == (self.start.x as usize, self.start.y as usize) => '+',
+                Tile::Air => '.',
+                Tile::Rock => '#',
+                Tile::Sand => 'o',
+            }).collect::<String>()
+        }).collect::<Vec<String>>().join("\n");
+        let units = match sand_counter {
+            1 => "unit",
+            _ => "units",
+        };
+        Frame::new(format!("{} {} of sand", sand_counter, units), picture)
     }
 }
 
+//Caves span a few hundred tiles, larger coordinates would only make a huge grid of air
+const MAX_COORDINATE: i32 = 10_000;
+
+fn coordinate(scan: &mut Scanner, expected: &str) -> Result<i32, ParseError> {
+    let start = *scan;
+    match scan.integer(expected)? {
+        c if (0..=MAX_COORDINATE).contains(&c) => Ok(c),
+        _ => Err(start.error(format!("{} from 0 to {}", expected, MAX_COORDINATE))),
+    }
+}
 
 impl FromStr for Cave {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.lines();
+        let mut scan = Scanner::new(s);
         let mut rocks = vec![];
 
-        for line in lines {
-            let rock_lines = line.split(" -> ").collect::<Vec<&str>>();
-            rock_lines.windows(2).for_each(|w| {
-                let from = (w[0].split(',').nth(0).unwrap().parse::<usize>().unwrap(), w[0].split(',').nth(1).unwrap().parse::<usize>().unwrap());
-                let to = (w[1].split(',').nth(0).unwrap().parse::<usize>().unwrap(), w[1].split(',').nth(1).unwrap().parse::<usize>().unwrap());
-                for x in usize::min(from.0, to.0)..=(usize::max(from.0, to.0)) {
-                    for y in usize::min(from.1, to.1)..=(usize::max(from.1, to.1)) {
-                        rocks.push((x, y));
-                    }
+        //A path goes on for as long as arrows follow its points, which is to the end of its line
+        while !scan.is_empty() {
+            let mut previous: Option<Point2> = None;
+            let rock_lines = scan.separated("->", |scan| {
+                let start = *scan;
+                let x = coordinate(scan, "an x coordinate")?;
+                scan.literal(",")?;
+                let point = Point2::new(x, coordinate(scan, "a y coordinate")?);
+                //Paths run along rows and columns of the cave, never diagonally
+                if previous.is_some_and(|p| p.x != point.x && p.y != point.y) {
+                    return Err(start.error("a point in a straight line from the one before"));
                 }
+                previous = Some(point);
+                Ok(point)
+            })?;
+            rock_lines.windows(2).for_each(|w| {
+                let (from, to) = (w[0], w[1]);
+                let step = (to - from).signum();
+                rocks.extend((0..=from.chebyshev(to)).map(|i| from + step * i));
             })
         }
 
-        let bbox = rocks.iter().chain(&[(500, 0)]).fold((usize::MAX, usize::MAX, usize::MIN, usize::MIN), |acc, (x, y)| {
-            (acc.0.min(*x), acc.1.min(*y), acc.2.max(*x), acc.3.max(*y))
+        let source = Point2::new(500, 0);
+        let (min, max) = rocks.iter().fold((source, source), |(min, max), rock| {
+            (Point2::new(min.x.min(rock.x), min.y.min(rock.y)), Point2::new(max.x.max(rock.x), max.y.max(rock.y)))
         });
 
-        let mut grid = vec![vec![false; (bbox.3 - bbox.1 + 1) as usize]; (bbox.2 - bbox.0 + 1) as usize];
-        rocks.iter().for_each(|(x, y)| grid[(x - bbox.0) as usize][(y - bbox.1) as usize] = true);
+        let mut grid = Grid::new((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize, Tile::Air);
+        rocks.iter().for_each(|&rock| grid[rock - min] = Tile::Rock);
 
-        let shape = ((bbox.2 - bbox.0 + 1) as i32, (bbox.3 - bbox.1 + 1) as i32);
-        let start = (500 - bbox.0 as i32, 0);
+        let start = source - min;
 
         Ok(
             Cave {
                 start,
-                shape,
                 grid,
             }
         )
@@ -1,150 +1,155 @@
-use std::ops::RangeInclusive;
 use std::str::FromStr;
-use std::time::Instant;
 
 use itertools::Itertools;
 
-use aoc2022::parse_to_vec;
-
-const INPUT: &str = include_str!("../input/2022/day15.txt");
-
-fn main() {
-    let start = Instant::now();
-    let sensors: Vec<Sensor> = parse_to_vec(INPUT, "\n").unwrap();
-
-    let part1 = {
-        let row = 2_000_000;
-        //Each sensor has at most 1 range of impossible locations on a row
-        let ranges = sensors.iter()
-            .filter_map(|s| match s.y_in_range(row) {
-                true => Some(s.min_x_in_range(row).unwrap()..=s.max_x_in_range(row).unwrap()),
-                false => None,
-            }).collect::<Vec<RangeInclusive<i32>>>();
-        //Merge overlapping ranges
-        let merged_ranges = merge_overlapping(&ranges);
-        //Count elements in the remaining ranges
-        let n_in_range = merged_ranges.iter().map(|r| r.end() - r.start() + 1).sum::<i32>();
-        //Already known beacons on this row do not count towards the total
-        let beacons_on_row = sensors.iter().filter(|s| s.b_y == row).map(|s| s.b_x).unique().collect::<Vec<i32>>();
-        let n_beacons_in_range = beacons_on_row.iter().filter(|x| merged_ranges.iter().any(|r| r.contains(x))).count() as i32;
+use crate::{parse_to_vec, ParseError, Point2, RangeSet, Scanner, Solution};
+use crate::generate::{Generate, Rng};
+use crate::solution::NoAnswer;
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<Sensor>;
+    type Part1 = i32;
+    type Part2 = Result<usize, NoAnswer>;
 
-        n_in_range - n_beacons_in_range
-    };
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_to_vec(input, "\n")
+    }
 
-    println!("Part 1: {}", part1);
+    fn part1(sensors: &Self::Input) -> i32 {
+        let row = search_size(sensors) / 2;
+        let covered = coverage(sensors, row);
+        //Already known beacons on this row do not count towards the total
+        let n_beacons_in_range = sensors.iter().filter(|s| s.beacon.y == row).map(|s| s.beacon.x).unique()
+            .filter(|&x| covered.contains(x))
+            .count();
 
-    let part2 = {
-        let bbox = (0, 4_000_000, 0, 4_000_000);
-        let (bbox_w, bbox_h) = (bbox.1 - bbox.0, bbox.3 - bbox.2);
-        let bbox_center = ((bbox.0 + bbox.1) / 2, (bbox.2 + bbox.3) / 2);
-        let bbox_corners = [(bbox.0, bbox.2), (bbox.0, bbox.3), (bbox.1, bbox.2), (bbox.1, bbox.3)];
+        covered.len() as i32 - n_beacons_in_range as i32
+    }
 
-        //Sensors without any coverage inside the bbox are not relevant
-        let relevant_sensors = sensors.iter()
-            .filter(|s| s.r + bbox_w/2 + bbox_h/2 >= (s.x - bbox_center.0).abs() + (s.y - bbox_center.1).abs())
-            .collect::<Vec<&Sensor>>();
+    fn part2(sensors: &Self::Input) -> Result<usize, NoAnswer> {
+        let size = search_size(sensors);
 
-        //Since there is only a single possible place for the distress beacon,
-        //its distance must be exactly r+1 from at least 2 sensors, only take into account these points
-        let loc = relevant_sensors.iter()
+        //Since there is only a single possible place for the distress beacon, its distance must be exactly r+1
+        //from at least 2 sensors unless it is on the edge of the search area, so only scan those rows for a gap
+        let mut candidate_rows = sensors.iter()
             .flat_map(|s| s.lines_at_distance(s.r + 1))
             .combinations(2)
             .filter_map(|lines| intersection(lines[0], lines[1]))
-            .filter(|(x, y)| (bbox.0..=bbox.1).contains(x) && (bbox.2..=bbox.3).contains(y))
-            .chain(bbox_corners.into_iter()) //cover edge case where the beacon is on a corner of the bbox
-            .find(|(x, y)| relevant_sensors.iter().all(|s| !s.in_range(*x, *y)))
-            .unwrap();
+            .map(|p| p.y)
+            .chain([0, size])
+            .filter(|y| (0..=size).contains(y))
+            .unique();
+        let loc = candidate_rows
+            .find_map(|y| coverage(sensors, y).gaps(0..=size).iter().next().map(|gap| Point2::new(*gap.start(), y)))
+            .ok_or_else(|| NoAnswer(format!("a spot no sensor covers from 0,0 to {},{}", size, size)))?;
+
+        Ok(loc.x as usize * 4_000_000 + loc.y as usize)
+    }
+}
+
+impl Generate for Day15 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        //Sensors near the origin make part 2 search a 20 by 20 area, in which the distress beacon hides at a random spot.
+        //Every sensor sees a beacon just closer than that spot, until it is the only one not covered
+        const AREA: i32 = 20;
+        let hidden = Point2::new(rng.range(0..=AREA), rng.range(0..=AREA));
+        //Two sensors on either side see it on crossing edges of their ranges, like part 2 expects
+        let mut sensors = vec![
+            hidden + Point2::new(-rng.range(1..=5), -rng.range(1..=5)),
+            hidden + Point2::new(rng.range(1..=5), -rng.range(1..=5)),
+        ];
+        let covered = |sensors: &[Point2], p: Point2| sensors.iter().any(|s| s.manhattan(p) < s.manhattan(hidden));
+        while sensors.len() < size || (0..=AREA).flat_map(|y| (0..=AREA).map(move |x| Point2::new(x, y)))
+            .any(|p| p != hidden && !covered(&sensors, p)) {
+            let sensor = Point2::new(rng.range(-5..=AREA + 5), rng.range(-5..=AREA + 5));
+            if sensor.manhattan(hidden) >= 2 {
+                sensors.push(sensor);
+            }
+        }
+        sensors.iter().map(|&sensor| {
+            let r = sensor.manhattan(hidden) - 1;
+            let dx = rng.range(-r..=r);
+            let dy = (r - dx.abs()) * *rng.pick(&[-1, 1]);
+            let beacon = sensor + Point2::new(dx, dy);
+            format!("Sensor at x={}, y={}: closest beacon is at x={}, y={}\n", sensor.x, sensor.y, beacon.x, beacon.y)
+        }).collect()
+    }
+}
 
-        loc.0 as usize * bbox_w as usize + loc.1 as usize
-    };
+//Positions on a row in range of any sensor; each sensor covers at most 1 range of a row
+fn coverage(sensors: &[Sensor], row: i32) -> RangeSet<i32> {
+    sensors.iter().filter_map(|s| Some(s.min_x_in_range(row)?..=s.max_x_in_range(row)?)).collect()
+}
 
-    println!("Part 2: {}", part2);
-    println!("Time: {:?}", start.elapsed());
+//The example searches up to 20 (and row 10), real inputs up to 4_000_000 (and row 2_000_000)
+fn search_size(sensors: &[Sensor]) -> i32 {
+    match sensors.iter().all(|s| s.pos.chebyshev(Point2::ORIGIN) <= 1_000) {
+        true => 20,
+        false => 4_000_000,
+    }
 }
 
 #[derive(Debug, Clone)]
-struct Sensor {
-    x: i32,
-    y: i32,
+pub struct Sensor {
+    pos: Point2,
     r: i32,
-    b_x: i32,
-    b_y: i32,
+    beacon: Point2,
 }
 
 impl Sensor {
-    fn in_range(&self, x: i32, y: i32) -> bool {
-        (self.x - x).abs() + (self.y - y).abs() <= self.r
-    }
-    fn y_in_range(&self, y: i32) -> bool {
-        (self.y - y).abs() <= self.r
-    }
     fn min_x_in_range(&self, y: i32) -> Option<i32> {
-        match self.r - (self.y - y).abs() {
+        match self.r - (self.pos.y - y).abs() {
             r if r < 0 => None,
-            r => Some(self.x - r),
+            r => Some(self.pos.x - r),
         }
     }
     fn max_x_in_range(&self, y: i32) -> Option<i32> {
-        match self.r - (self.y - y).abs() {
+        match self.r - (self.pos.y - y).abs() {
             r if r < 0 => None,
-            r => Some(self.x + r),
+            r => Some(self.pos.x + r),
         }
     }
-    fn lines_at_distance(&self, d: i32) -> [((i32,i32),(i32,i32));4]{
-        let x = self.x;
-        let y = self.y;
+    fn lines_at_distance(&self, d: i32) -> [(Point2, Point2); 4]{
+        let (p, dx, dy) = (self.pos, Point2::new(d, 0), Point2::new(0, d));
         [
-            ((x, y+d), (x+d, y)), //ne
-            ((x+d, y), (x, y-d)), //se
-            ((x, y-d), (x-d, y)), //sw
-            ((x-d, y), (x, y+d)), //nw
+            (p + dy, p + dx), //ne
+            (p + dx, p - dy), //se
+            (p - dy, p - dx), //sw
+            (p - dx, p + dy), //nw
         ]
     }
 }
 
 impl FromStr for Sensor {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let sp = s.split(' ').collect::<Vec<&str>>();
-        if sp.len() != 10 { return Err(()); }
-        let x = sp[2][2..sp[2].len() - 1].parse::<i32>().unwrap();
-        let y = sp[3][2..sp[3].len() - 1].parse::<i32>().unwrap();
-        let b_x = sp[8][2..sp[8].len() - 1].parse::<i32>().unwrap();
-        let b_y = sp[9][2..sp[9].len()].parse::<i32>().unwrap();
-        let r = (x - b_x).abs() + (y - b_y).abs();
-        Ok(Sensor { x, y, r, b_x, b_y })
-    }
-}
-
-fn merge_overlapping(ranges: &[RangeInclusive<i32>]) -> Vec<RangeInclusive<i32>> {
-    let mut merged = Vec::from(ranges);
-    let overlap = |r1: &RangeInclusive<i32>, r2: &RangeInclusive<i32>| { r1.start().max(r2.start()) <= r1.end().min(r2.end()) };
-    'overlap: loop {
-        for i in 0..merged.len() {
-            for j in i + 1..merged.len() {
-                if overlap(&merged[i], &merged[j]) {
-                    merged[i] = *merged[i].start().min(merged[j].start())..=*merged[i].end().max(merged[j].end());
-                    merged.swap_remove(j);
-                    continue 'overlap;
-                }
-            }
-        }
-        break; //no more overlapping ranges
+        let mut scan = Scanner::new(s);
+        let mut point = |before: &str| -> Result<Point2, ParseError> {
+            scan.literal(before)?;
+            let x = scan.integer("an x coordinate")?;
+            scan.literal(", y=")?;
+            Ok(Point2::new(x, scan.integer("a y coordinate")?))
+        };
+        let pos = point("Sensor at x=")?;
+        let beacon = point(": closest beacon is at x=")?;
+        scan.end()?;
+        Ok(Sensor { pos, r: pos.manhattan(beacon), beacon })
     }
-    merged
 }
 
-fn intersection(l1: ((i32,i32),(i32,i32)), l2: ((i32,i32),(i32,i32))) -> Option<(i32,i32)>{
-    let (x1, y1) = (l1.0.0 as i128, l1.0.1 as i128);
-    let (x2, y2) = (l1.1.0 as i128, l1.1.1 as i128);
-    let (x3, y3) = (l2.0.0 as i128, l2.0.1 as i128);
-    let (x4, y4) = (l2.1.0 as i128, l2.1.1 as i128);
+fn intersection(l1: (Point2, Point2), l2: (Point2, Point2)) -> Option<Point2>{
+    let (x1, y1) = (l1.0.x as i128, l1.0.y as i128);
+    let (x2, y2) = (l1.1.x as i128, l1.1.y as i128);
+    let (x3, y3) = (l2.0.x as i128, l2.0.y as i128);
+    let (x4, y4) = (l2.1.x as i128, l2.1.y as i128);
 
     let d = (x1 - x2) * (y3 - y4) - (y1 - y2) * (x3 - x4);
     if d == 0 { return None; }
 
     let x = ((x1 * y2 - y1 * x2) * (x3 - x4) - (x1 - x2) * (x3 * y4 - y3 * x4)) / d;
     let y = ((x1 * y2 - y1 * x2) * (y3 - y4) - (y1 - y2) * (x3 * y4 - y3 * x4)) / d;
-    Some((x as i32, y as i32))
+    Some(Point2::new(x as i32, y as i32))
 }
\ No newline at end of file
@@ -1,147 +1,181 @@
-use std::cmp::{Reverse};
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
-use std::time::Instant;
 
 use itertools::Itertools;
 
-const INPUT: &str = include_str!("../input/2022/day16.txt");
+use crate::{ParseError, Scanner, Solution};
+use crate::checkpoint::{Checkpoint, Resume};
+use crate::search;
+use crate::generate::{Generate, Rng};
 
-fn main() {
-    let start = Instant::now();
-    let network = INPUT.parse::<Network>().unwrap();
+pub struct Day16;
 
-    let part_1 = {
+impl Solution for Day16 {
+    type Input = Network;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(network: &Self::Input) -> usize {
         let mut active_valves = vec![false; network.valves.len()];
         active_valves[network.start] = true;
-        dfs(&network, network.start, 0, 0, 30, &mut active_valves)
-    };
-    println!("Part 1: {}", part_1);
-
-    let part_2 = {
-        let non_zero_pressure_valves = network.valves.iter().enumerate()
-            .filter(|(_, v)| v.pressure_rate != 0).map(|(i, _)| i).collect_vec();
-        let mut already_seen = HashSet::new();
-        //Collect all unique (dual) subsets of active valves
-        let mut groups : Vec<(Vec<bool>, Vec<bool>)> = Vec::new();
-        for i in 1..=non_zero_pressure_valves.len() {
-            for group in non_zero_pressure_valves.iter().combinations(i) {
-                let mut this = vec![false; network.valves.len()];
-                group.iter().for_each(|i| this[**i] = true);
-                let that = this.iter().enumerate().map(|(i,&b)| {
-                    match non_zero_pressure_valves.contains(&i) {
-                        false => false,
-                        true => !b,
-                    }
-                }).collect_vec();
-                if !already_seen.contains(&this){
-                    already_seen.insert(this.clone());
-                    already_seen.insert(that.clone());
-                    groups.push((this, that));
+        dfs(network, network.start, 0, 0, 30, &mut active_valves)
+    }
+
+    fn part2(network: &Self::Input) -> usize {
+        best_split(network, &Checkpoint::off())
+    }
+}
+
+impl Resume for Day16 {
+    fn resume(network: &Self::Input, part: u8, checkpoint: &Checkpoint) -> String {
+        match part {
+            1 => Self::part1(network).to_string(),
+            _ => best_split(network, checkpoint).to_string(),
+        }
+    }
+}
+
+//Most pressure released by splitting the valves between me and the elephant; every split is a unit of the checkpoint
+fn best_split(network: &Network, checkpoint: &Checkpoint) -> usize {
+    let non_zero_pressure_valves = network.valves.iter().enumerate()
+        .filter(|(_, v)| v.pressure_rate != 0).map(|(i, _)| i).collect_vec();
+    let mut already_seen = HashSet::new();
+    //Collect all unique (dual) subsets of active valves
+    let mut groups : Vec<(Vec<bool>, Vec<bool>)> = Vec::new();
+    for i in 1..=non_zero_pressure_valves.len() {
+        for group in non_zero_pressure_valves.iter().combinations(i) {
+            let mut this = vec![false; network.valves.len()];
+            group.iter().for_each(|i| this[**i] = true);
+            let that = this.iter().enumerate().map(|(i,&b)| {
+                match non_zero_pressure_valves.contains(&i) {
+                    false => false,
+                    true => !b,
                 }
+            }).collect_vec();
+            if !already_seen.contains(&this){
+                already_seen.insert(this.clone());
+                already_seen.insert(that.clone());
+                groups.push((this, that));
             }
         }
-        let n_groups = groups.len();
-        let mut counter = 0;
-        groups.drain(..).map(|(mut me, mut elephant)|{
-            if counter % 100 == 0 {
-                println!("{} / {}", counter, n_groups);
+    }
+    let n_groups = groups.len();
+    log::debug!("{} ways to split the valves with flow", n_groups);
+    groups.drain(..).enumerate().map(|(counter, (mut me, mut elephant))|{
+        if let Some(pressure) = checkpoint.get(counter) {
+            return pressure as usize;
+        }
+        if counter % 100 == 0 {
+            log::debug!("{} / {} splits", counter, n_groups);
+        }
+
+        //Find the optimal path for each group
+        me[network.start] = true;
+        elephant[network.start] = true;
+        let pressure = dfs(network, network.start, 0, 0, 26, &mut me) +
+            dfs(network, network.start, 0, 0, 26, &mut elephant);
+        checkpoint.complete(counter, pressure as u64);
+        pressure
+    }).max().unwrap()
+}
+
+impl Generate for Day16 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        let n = size.clamp(2, 26 * 26);
+        let names = (0..n).map(|i| format!("{}{}", (b'A' + (i / 26) as u8) as char, (b'A' + (i % 26) as u8) as char)).collect_vec();
+        //A tree of tunnels keeps every valve reachable, and some more tunnels add loops
+        let mut tunnels = vec![vec![]; n];
+        let extra = (0..n / 3).map(|_| (rng.range(0..=n - 1), rng.range(0..=n - 1))).collect_vec();
+        for (a, b) in (1..n).map(|a| (a, rng.range(0..=a - 1))).chain(extra) {
+            if a != b && !tunnels[a].contains(&b) {
+                tunnels[a].push(b);
+                tunnels[b].push(a);
+            }
+        }
+        //Only a few valves (never AA) have a flow rate, like in the real inputs
+        let mut flow_rates = vec![0; n];
+        let mut valves = (1..n).collect_vec();
+        rng.shuffle(&mut valves);
+        valves.iter().take(rng.range(1..=(n - 1).min(10))).for_each(|&v| flow_rates[v] = rng.range(1..=25));
+
+        (0..n).map(|v| {
+            let to = tunnels[v].iter().map(|&t| names[t].as_str()).join(", ");
+            match tunnels[v].len() {
+                1 => format!("Valve {} has flow rate={}; tunnel leads to valve {}\n", names[v], flow_rates[v], to),
+                _ => format!("Valve {} has flow rate={}; tunnels lead to valves {}\n", names[v], flow_rates[v], to),
             }
-            counter += 1;
-
-            //Find the optimal path for each group
-            me[network.start] = true;
-            elephant[network.start] = true;
-            dfs(&network, network.start, 0, 0, 26, &mut me) +
-                dfs(&network, network.start, 0, 0, 26, &mut elephant)
-        }).max().unwrap()
-    };
-
-    println!("Part 2: {}", part_2);
-    println!("Time: {:?}", start.elapsed());
+        }).collect()
+    }
 }
 
-struct Network {
+pub struct Network {
     pub valves: Vec<Valve>,
     pub sp_map: HashMap<(usize, usize), usize>,
     pub start: usize,
 }
 
-struct Valve {
+pub struct Valve {
     pressure_rate: usize,
 }
 
 impl FromStr for Network {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let valves = s.lines()
-            .map(|l| l.split(';').next().unwrap())
-            .map(|l| l.parse::<Valve>().unwrap())
-            .collect::<Vec<Valve>>();
-        let name_map = s.lines().enumerate()
-            .map(|(i, l)| (l.split(' ').nth(1).unwrap().to_string(), i))
+        let lines = s.lines().filter(|l| !l.trim().is_empty())
+            .map(|l| scan_valve(l).map_err(|e| e.within(s, l)))
+            .collect::<Result<Vec<_>, ParseError>>()?;
+        let name_map = lines.iter().enumerate()
+            .map(|(i, (name, _, _))| (name.to_string(), i))
             .collect::<HashMap<String, usize>>();
 
-        let edges = s.lines()
-            .map(|l| {
-                l.split(' ').skip(9)
-                    .map(|c| c.strip_suffix(',').unwrap_or(c))
-                    .map(|c| *name_map.get(c).unwrap())
-                    .collect::<Vec<usize>>()
-            }).collect();
+        let edges = lines.iter()
+            .map(|(_, _, tunnels)| {
+                tunnels.iter()
+                    .map(|c| name_map.get(*c).copied().ok_or_else(|| ParseError::at(s, c, "a known valve name")))
+                    .collect::<Result<Vec<usize>, ParseError>>()
+            }).collect::<Result<Vec<Vec<usize>>, ParseError>>()?;
+        let valves = lines.iter().map(|&(_, pressure_rate, _)| Valve { pressure_rate }).collect();
 
         let sp_map = shortest_path_matrix(&edges);
-        let start = *name_map.get("AA").unwrap();
+        let start = *name_map.get("AA").ok_or_else(|| ParseError::end(s, "a valve named AA"))?;
 
         Ok(Network { valves, sp_map, start })
     }
 }
 
-impl FromStr for Valve {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let flow_rate = s.split('=').nth(1).unwrap().parse::<usize>().unwrap();
-        Ok(Valve { pressure_rate: flow_rate })
-    }
+//Name, flow rate and tunnels of a line like "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB"
+fn scan_valve(line: &str) -> Result<(&str, usize, Vec<&str>), ParseError> {
+    let mut scan = Scanner::new(line);
+    scan.literal("Valve")?;
+    let name = scan.identifier("a valve name")?;
+    scan.literal("has flow rate=")?;
+    let flow_rate = scan.integer("a flow rate")?;
+    scan.literal(";")?;
+    scan.one_of(&["tunnels lead to valves", "tunnel leads to valve"])?;
+    let tunnels = scan.separated(",", |scan| scan.identifier("a valve name"))?;
+    scan.end()?;
+    Ok((name, flow_rate, tunnels))
 }
 
-fn shortest_path_matrix(edges: &Vec<Vec<usize>>) -> HashMap<(usize, usize), usize> {
-    //Floyd-Warshall would be faster here, but I'm lazy and this is certainly not the bottleneck today...
+fn shortest_path_matrix(edges: &[Vec<usize>]) -> HashMap<(usize, usize), usize> {
+    //Every tunnel takes a minute, so a breadth-first search from each valve finds the distances to all others
+    let graph = |valve: &usize| edges[*valve].iter().map(|&to| (to, 1));
     let mut shortest_paths = HashMap::new();
     for start in 0..edges.len() {
-        for end in start + 1..edges.len() {
-            let sp = dijkstra(edges, start, end);
-            shortest_paths.insert((start, end), sp);
-            shortest_paths.insert((end, start), sp);
+        let explored = search::explore(&graph, [start]);
+        for end in 0..edges.len() {
+            shortest_paths.insert((start, end), explored.cost(&end).unwrap_or(usize::MAX));
         }
     }
     shortest_paths
 }
 
-fn dijkstra(edges: &Vec<Vec<usize>>, start: usize, end: usize) -> usize {
-    let mut distances = vec![usize::MAX; edges.len()];
-    distances[start] = 0;
-    let mut queue = BinaryHeap::new();
-    queue.push(Reverse((0, start))); //Reverse to make it a min heap
-
-    while let Some(Reverse((distance, node))) = queue.pop() {
-        if node == end {
-            break;
-        }
-        for to in &edges[node] {
-            let new_distance = distance + 1;
-            if new_distance < distances[*to] {
-                distances[*to] = new_distance;
-                queue.push(Reverse((new_distance, *to)));
-            }
-        }
-    }
-    distances[end]
-}
-
-fn dfs(network: &Network, location: usize, mut total_pressure: usize, mut pressure_rate: usize, mut time_left: usize, active_valves: &mut Vec<bool>) -> usize {
+fn dfs(network: &Network, location: usize, mut total_pressure: usize, mut pressure_rate: usize, mut time_left: usize, active_valves: &mut [bool]) -> usize {
     if time_left == 0 {
         return total_pressure;
     }
@@ -1,93 +1,113 @@
 use std::str::FromStr;
 
 use itertools::Itertools;
-use fxhash::FxHashMap;
 
-use aoc2022::parse_to_vec;
+use crate::{parse_to_vec, Grid, ParseError, Solution};
+use crate::cycle;
+use crate::visualize::{Frame, Recorder, Visualize};
+use crate::generate::{Generate, Rng};
+use crate::solution::NoAnswer;
 
 const ROCKS: &str = include_str!("../input/2022/day17_rock.txt");
-const GAS_PATTERN: &str = include_str!("../input/2022/day17.txt");
 const SHAFT_WIDTH: usize = 7;
+//Rows at the top of the shaft shown in every frame
+const VISIBLE_ROWS: usize = 30;
+//Rows below the top of the tower that rocks may still fall into. Puzzle inputs leave about a hundred open, while a column
+//that the rocks never fill leaves ever more, and then the top never repeats
+const MAX_OPEN_ROWS: usize = 1000;
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = (Vec<RockType>, Vec<char>);
+    type Part1 = usize;
+    type Part2 = Result<usize, NoAnswer>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let gas_pattern = input.trim();
+        if let Some((i, c)) = gas_pattern.char_indices().find(|(_, c)| !matches!(c, '<' | '>')) {
+            return Err(ParseError::at(input, &gas_pattern[i..i + c.len_utf8()], "a jet of gas (< or >)"));
+        }
+        if gas_pattern.is_empty() {
+            return Err(ParseError::end(input, "a jet of gas (< or >)"));
+        }
+        Ok((parse_to_vec::<RockType>(ROCKS, "\n\n")?, gas_pattern.chars().collect_vec()))
+    }
 
-fn main() {
-    let start = std::time::Instant::now();
-    let rock_types = parse_to_vec::<RockType>(ROCKS, "\n\n").unwrap();
-    let gas_pattern = GAS_PATTERN.trim().chars().collect_vec();
-
-    let part_1 = {
-        let mut shaft = Shaft::new(&rock_types, &gas_pattern);
-        shaft.simulate(2022);
-        shaft.rock_height()
-    };
-
-    println!("Part 1: {}", part_1);
-    println!("Time: {:?}", start.elapsed());
-
-    let part_2 = {
-        //Search a cycle: two separate iterations where the shaft, rocks and gas patterns are in the same state
-        let ((cycle_start, height_start), (cycle_end, height_end)) = {
-            let mut shaft = Shaft::new(&rock_types, &gas_pattern);
-            let mut state_map = FxHashMap::default();
-
-            let mut cycle = None;
-            for i in 0..1_000_000_000_000 {
-                shaft.simulate(1);
-                let minimal_shape = shaft.minimal_shape(); //Use the 'minimal' shape instead of entire shape in the state
-                let state = (minimal_shape, shaft.gas_index, shaft.rock_index);
-                if state_map.contains_key(&state) {
-                    cycle = Some((state_map[&state], (i, shaft.rock_height())));
-                    break;
-                }
-                state_map.insert(state, (i, shaft.rock_height()));
-            }
-            cycle.unwrap()
-        };
-
-        //---<before>---/-<c>-/-<c>-/ . . . /-<c>-/-<c>-/---<remainder>---/
-
-        let n_cycles = (1_000_000_000_000 - cycle_start) / (cycle_end - cycle_start);
-        let remainder = (1_000_000_000_000 - cycle_start) % (cycle_end - cycle_start);
-
-        //Determine which height the remainder contributes
-        let remaining_height = {
-            let mut shaft = Shaft::new(&rock_types, &gas_pattern);
-            shaft.simulate(cycle_end + remainder);
-            shaft.rock_height() - height_end
-        };
-        //Add all of them up to get the total height
-        height_start + (height_end - height_start) * n_cycles + remaining_height
-    };
-
-    println!("Part 2: {:?}", part_2);
-    println!("Time: {:?}", start.elapsed());
+    fn part1(input: &Self::Input) -> usize {
+        tower_height(input, &mut Recorder::off())
+    }
+
+    fn part2(input: &Self::Input) -> Result<usize, NoAnswer> {
+        extrapolated_tower_height(input, &mut Recorder::off())
+    }
+}
+
+impl Generate for Day17 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        //Short patterns can keep a column of the shaft empty forever, and then the shape of the top never repeats
+        (0..size.max(1) * 100).map(|_| *rng.pick(&['<', '>'])).chain(['\n']).collect()
+    }
+}
+
+impl Visualize for Day17 {
+    fn visualize(input: &Self::Input, part: u8, recorder: &mut Recorder) {
+        match part {
+            1 => { tower_height(input, recorder); },
+            _ => { let _ = extrapolated_tower_height(input, recorder); },
+        }
+    }
+}
+
+fn tower_height((rock_types, gas_pattern): &(Vec<RockType>, Vec<char>), recorder: &mut Recorder) -> usize {
+    let mut shaft = Shaft::new(rock_types, gas_pattern);
+    shaft.simulate(2022, recorder);
+    shaft.rock_height()
+}
+
+fn extrapolated_tower_height((rock_types, gas_pattern): &(Vec<RockType>, Vec<char>), recorder: &mut Recorder) -> Result<usize, NoAnswer> {
+    //Drop rocks one by one until the shaft, rocks and gas pattern are in a state seen before,
+    //from then on every cycle of rocks adds the same height
+    let cycle = cycle::find_cycle(
+        Shaft::new(rock_types, gas_pattern),
+        |shaft| shaft.simulate(1, recorder),
+        |shaft| {
+            let shape = shaft.minimal_shape(); //Use the 'minimal' shape instead of entire shape in the state
+            (shape.len() <= MAX_OPEN_ROWS * SHAFT_WIDTH).then_some((shape, shaft.gas_index, shaft.rock_index))
+        },
+        |shaft| shaft.rock_height() as i64,
+    ).ok_or_else(|| NoAnswer(format!("a jet pattern leaving at most {} rows open below the top of the tower", MAX_OPEN_ROWS)))?;
+    Ok(cycle.value_after(1_000_000_000_000) as usize)
 }
 
 struct Shaft<'a> {
-    pub shape: Vec<[bool; SHAFT_WIDTH]>,
-    pub rock_types: &'a Vec<RockType>,
+    pub shape: Grid<bool>, //bottom row first
+    pub rock_types: &'a [RockType],
     pub rock_index: usize,
-    pub gas_pattern: &'a Vec<char>,
+    pub gas_pattern: &'a [char],
     pub gas_index: usize,
+    pub n_rocks: usize,
 }
 
 impl<'a> Shaft<'a> {
-    pub fn new(rock_types: &'a Vec<RockType>, gas_pattern: &'a Vec<char>) -> Self {
+    pub fn new(rock_types: &'a [RockType], gas_pattern: &'a [char]) -> Self {
         Shaft {
-            shape: Vec::new(),
+            shape: Grid::new(SHAFT_WIDTH, 0, false),
             rock_types,
             rock_index: 0,
             gas_pattern,
             gas_index: 0,
+            n_rocks: 0,
         }
     }
 
-    pub fn simulate(&mut self, n_rocks: usize) {
+    pub fn simulate(&mut self, n_rocks: usize, recorder: &mut Recorder) {
         enum Mode { Falling, Gas, Stuck }
 
         for _ in 0..n_rocks {
             let rock_type = &self.rock_types[self.rock_index];
             self.rock_index = (self.rock_index + 1) % self.rock_types.len();
-            let (mut pos, mut mode) = ((2, self.shape.len() + 3), Mode::Gas); //start state
+            let (mut pos, mut mode) = ((2, self.shape.height() + 3), Mode::Gas); //start state
 
             loop {
                 mode = match mode {
@@ -113,7 +133,7 @@ impl<'a> Shaft<'a> {
                     }
                     Mode::Falling => match pos.1 == 0 {
                         true => Mode::Stuck, //Bottom of shaft reached
-                        false => match pos.1 < self.shape.len() + 1 {
+                        false => match pos.1 < self.shape.height() + 1 {
                             true => match self.rock_collides(rock_type, (pos.0, pos.1 - 1)) { //Rock is in range of other rocks, watch out for collisions
                                 true => Mode::Stuck,
                                 false => {
@@ -131,11 +151,13 @@ impl<'a> Shaft<'a> {
                         rock_type.shape.iter()
                             .map(|(x, y)| (pos.0 + *x as usize, pos.1 + *y as usize))
                             .for_each(|(x, y)| {
-                                if self.shape.len() <= y {
-                                    self.shape.push([false; SHAFT_WIDTH]);
+                                if self.shape.height() <= y {
+                                    self.shape.push_row([false; SHAFT_WIDTH]);
                                 }
-                                self.shape[y][x] = true;
+                                self.shape[(x, y)] = true;
                             });
+                        self.n_rocks += 1;
+                        recorder.frame(|| self.frame());
                         break;
                     }
                 }
@@ -146,16 +168,16 @@ impl<'a> Shaft<'a> {
     fn rock_collides(&self, rock_type: &RockType, rel_pos: (usize, usize)) -> bool {
         rock_type.shape.iter()
             .map(|(x, y)| (rel_pos.0 + *x as usize, rel_pos.1 + *y as usize))
-            .any(|(x, y)| self.shape.get(y).unwrap_or(&[false; SHAFT_WIDTH])[x])
+            .any(|(x, y)| self.shape.get(x as i32, y as i32) == Some(&true))
     }
 
-    pub fn minimal_shape(&self) -> Vec<[bool; SHAFT_WIDTH]> {
+    pub fn minimal_shape(&self) -> Vec<bool> {
         //Only the rows from the top until the row which is fully in the 'shadow' of rocks above are relevant
         let mut sunlight_reach = [true; SHAFT_WIDTH];
         let mut cutoff_index = 0;
 
-        for i in (0..self.shape.len()).rev() {
-            (0..SHAFT_WIDTH).filter(|x| self.shape[i][*x]) //Update sunlight map
+        for i in (0..self.shape.height()).rev() {
+            (0..SHAFT_WIDTH).filter(|x| self.shape[(*x, i)]) //Update sunlight map
                 .for_each(|x| sunlight_reach[x] = false);
             if sunlight_reach.iter().all(|&x| !x) { //No sunlight left
                 cutoff_index = i;
@@ -163,21 +185,29 @@ impl<'a> Shaft<'a> {
             }
         }
 
-        (cutoff_index..self.shape.len()).map(|i| self.shape[i]).collect()
+        self.shape.rows().skip(cutoff_index).flatten().copied().collect()
     }
 
     pub fn rock_height(&self) -> usize {
-        self.shape.len()
+        self.shape.height()
+    }
+
+    //The top of the tower, between the walls of the shaft
+    fn frame(&self) -> Frame {
+        let picture = self.shape.rows().rev().take(VISIBLE_ROWS)
+            .map(|row| format!("|{}|", row.iter().map(|&rock| if rock { '#' } else { '.' }).collect::<String>()))
+            .join("\n");
+        Frame::new(format!("{} rocks, {} high", self.n_rocks, self.rock_height()), picture)
     }
 }
 
-struct RockType {
+pub struct RockType {
     pub shape: Vec<(u8, u8)>,
     pub width: u8,
 }
 
 impl FromStr for RockType {
-    type Err = ();
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut shape = Vec::new();
         for (y, line) in s.lines().rev().enumerate() {
@@ -187,7 +217,7 @@ impl FromStr for RockType {
                 }
             }
         }
-        let width = shape.iter().map(|(x, _)| x).max().unwrap() + 1;
+        let width = shape.iter().map(|(x, _)| x).max().ok_or_else(|| ParseError::at(s, s, "a rock shape"))? + 1;
         Ok(RockType { shape, width })
     }
 }
@@ -1,24 +1,52 @@
-use std::collections::HashSet;
-use std::hash::BuildHasherDefault;
 use std::str::FromStr;
 
-use fxhash::{FxHasher, FxHashSet};
+use fxhash::FxHashSet;
 use itertools::Itertools;
 
-const INPUT: &str = include_str!("../input/2022/day18.txt");
+use crate::{ParseError, Point3, Scanner, Solution};
+use crate::search::{self, Explored};
+use crate::generate::{Generate, Rng};
 
-fn main() {
-    let start = std::time::Instant::now();
-    let droplet = INPUT.parse::<Droplet>().unwrap();
+pub struct Day18;
 
-    println!("Part 1: {}", droplet.total_surface_area());
-    println!("Part 2: {}", droplet.exterior_surface_area());
-    println!("Time: {:?}", start.elapsed());
+impl Solution for Day18 {
+    type Input = Droplet;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(droplet: &Self::Input) -> usize {
+        droplet.total_surface_area()
+    }
+
+    fn part2(droplet: &Self::Input) -> usize {
+        droplet.exterior_surface_area()
+    }
+}
+
+impl Generate for Day18 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        //Cubes packed in a box about twice their volume, so they touch and trap some air
+        let n = size.max(1);
+        let side = ((2 * n) as f64).cbrt().ceil() as i32 + 1;
+        let mut cubes = FxHashSet::default();
+        let mut scan = String::new();
+        while cubes.len() < n {
+            let cube = Point3::new(rng.range(1..=side), rng.range(1..=side), rng.range(1..=side));
+            if cubes.insert(cube) {
+                scan.push_str(&format!("{},{},{}\n", cube.x, cube.y, cube.z));
+            }
+        }
+        scan
+    }
 }
 
 
-struct Droplet {
-    cubes: Vec<(i32, i32, i32)>,
+pub struct Droplet {
+    cubes: Vec<Point3>,
 }
 
 impl Droplet {
@@ -27,78 +55,74 @@ impl Droplet {
         self.cubes.iter().for_each(|c| { cube_set.insert(c); });
 
         self.cubes.iter().map(|c| {
-            get_neighbors(*c).iter().filter(|n| !cube_set.contains(n)).count()
+            c.neighbors6().iter().filter(|n| !cube_set.contains(n)).count()
         }).sum()
     }
 
     fn exterior_surface_area(&self) -> usize {
-        let mut cube_set = FxHashSet::default();
-        self.cubes.iter().for_each(|c| { cube_set.insert(c); });
-        let (x_min, x_max) = self.cubes.iter().map(|c| c.0).minmax().into_option().unwrap();
-        let (y_min, y_max) = self.cubes.iter().map(|c| c.1).minmax().into_option().unwrap();
-        let (z_min, z_max) = self.cubes.iter().map(|c| c.2).minmax().into_option().unwrap();
+        let cube_set = self.cubes.iter().copied().collect::<FxHashSet<Point3>>();
+        let (x_min, x_max) = self.cubes.iter().map(|c| c.x).minmax().into_option().unwrap();
+        let (y_min, y_max) = self.cubes.iter().map(|c| c.y).minmax().into_option().unwrap();
+        let (z_min, z_max) = self.cubes.iter().map(|c| c.z).minmax().into_option().unwrap();
 
+        //Leave a layer of air around the droplet so the exterior is connected
+        let exterior = exterior((Point3::new(x_min - 1, y_min - 1, z_min - 1), Point3::new(x_max + 1, y_max + 1, z_max + 1)), &cube_set);
         self.cubes.iter().map(|c| {
-            get_neighbors(*c).iter()
-                .filter(|n| !cube_set.contains(n))
-                .filter(|n| cube_exterior(**n, ((x_min, y_min, z_min), (x_max, y_max, z_max)), &cube_set))
-                .count()
+            c.neighbors6().iter().filter(|n| exterior.contains(n)).count()
         }).sum()
     }
 }
 
-fn get_neighbors(c: (i32, i32, i32)) -> [(i32, i32, i32); 6] {
-    let (x, y, z) = c;
-    [
-        (x + 1, y, z),
-        (x - 1, y, z),
-        (x, y + 1, z),
-        (x, y - 1, z),
-        (x, y, z + 1),
-        (x, y, z - 1),
-    ]
-}
-
-fn cube_exterior(c: (i32, i32, i32), bbox: ((i32, i32, i32), (i32, i32, i32)), cube_set: &HashSet<&(i32, i32, i32), BuildHasherDefault<FxHasher>>) -> bool {
-    //Flood fill until we reach a cube outside the bbox, or have explored all neighbors
-    //There is probably some heuristic to optimize the candidate order, but this is fast enough
-    let mut next_candidates = vec![c];
-    let mut visited = FxHashSet::default();
-
-    while !next_candidates.is_empty() {
-        let current = next_candidates.pop().unwrap();
-        if visited.contains(&current) {
-            continue;
-        }
-        visited.insert(current);
-        let (x, y, z) = current;
-        if x < bbox.0.0 || x > bbox.1.0 || y < bbox.0.1 || y > bbox.1.1 || z < bbox.0.2 || z > bbox.1.2 {
-            return true;
-        }
-        if !cube_set.contains(&current) {
-            get_neighbors(current).iter().for_each(|n| {
-                next_candidates.push(*n);
-            });
-        }
-    }
-    false
+fn exterior(bbox: (Point3, Point3), cube_set: &FxHashSet<Point3>) -> Explored<Point3> {
+    //Flood fill the air inside the bbox, starting from a corner which is outside the droplet
+    let in_bbox = |c: Point3| {
+        c.x >= bbox.0.x && c.x <= bbox.1.x && c.y >= bbox.0.y && c.y <= bbox.1.y && c.z >= bbox.0.z && c.z <= bbox.1.z
+    };
+    let air = |c: &Point3| {
+        c.neighbors6().into_iter()
+            .filter(|&n| in_bbox(n) && !cube_set.contains(&n))
+            .map(|n| (n, 1))
+    };
+    search::explore(&air, [bbox.0])
 }
 
 impl FromStr for Droplet {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let cubes = s.lines().filter_map(
             |line| {
                 match line.is_empty() {
                     true => None,
-                    false => {
-                        let split = line.split(',').collect_vec();
-                        Some((split[0].parse().unwrap(), split[1].parse().unwrap(), split[2].parse().unwrap()))
-                    }
+                    false => Some(parse_cube(line).map_err(|e| e.within(s, line))),
                 }
             }
-        ).collect_vec();
+        ).collect::<Result<Vec<_>, ParseError>>()?;
+        if cubes.is_empty() {
+            return Err(ParseError::end(s, "a cube"));
+        }
         Ok(Droplet { cubes })
     }
+}
+
+//Droplets span a few dozen cubes, larger coordinates would only make a huge box of air to search around them
+const MAX_COORDINATE: i32 = 100;
+
+fn coordinate(scan: &mut Scanner, expected: &str) -> Result<i32, ParseError> {
+    let start = *scan;
+    match scan.integer(expected)? {
+        c if (-MAX_COORDINATE..=MAX_COORDINATE).contains(&c) => Ok(c),
+        _ => Err(start.error(format!("{} from {} to {}", expected, -MAX_COORDINATE, MAX_COORDINATE))),
+    }
+}
+
+fn parse_cube(line: &str) -> Result<Point3, ParseError> {
+    let mut scan = Scanner::new(line);
+    let x = coordinate(&mut scan, "an x coordinate")?;
+    scan.literal(",")?;
+    let y = coordinate(&mut scan, "a y coordinate")?;
+    scan.literal(",")?;
+    let z = coordinate(&mut scan, "a z coordinate")?;
+    scan.end()?;
+    Ok(Point3::new(x, y, z))
 }
\ No newline at end of file
@@ -1,46 +1,79 @@
 use std::str::FromStr;
-use std::time::Instant;
 
 use fxhash::{FxHashMap};
-use itertools::{Itertools, izip};
-
-use aoc2022::parse_to_vec;
-
-const INPUT: &str = include_str!("../input/2022/day19.txt");
-
-fn main() {
-    let start = Instant::now();
-    let blueprints = parse_to_vec::<Blueprint>(INPUT, "\n").unwrap();
-    let part_1 = {
-        let mut results = vec![None; blueprints.len()];
-        rayon::scope(|s| {
-            for (i, result) in results.iter_mut().enumerate() {
-                let blueprint = &blueprints[i];
-                s.spawn(move |_| {
-                    *result = Some(blueprint.simulate(SimState::new(24, [1, 0, 0, 0], [0;4])));
-                });
-            }
-        });
-        results.iter().enumerate().map(|(i, r)| (i + 1) * r.unwrap() as usize).sum::<usize>()
-    };
-
-    println!("Part 1: {}", part_1);
-
-    let part_2 = {
-        let mut results = vec![None; 3];
-        rayon::scope(|s| {
-            for (i, result) in results.iter_mut().enumerate() {
-                let blueprint = &blueprints[i];
-                s.spawn(move |_| {
-                    *result = Some(blueprint.simulate(SimState::new(32, [1, 0, 0, 0], [0;4])));
-                });
-            }
-        });
-        results.iter().fold(1, |acc, r| acc * r.unwrap() as usize)
-    };
+use itertools::{izip, Itertools};
+
+use crate::{logging, parse_to_vec, ParseError, Scanner, Solution};
+use crate::checkpoint::{Checkpoint, Resume};
+use crate::generate::{Generate, Rng};
+
+const RESOURCES: [&str; 4] = ["ore", "clay", "obsidian", "geode"];
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Vec<Blueprint>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_to_vec::<Blueprint>(input, "\n")
+    }
+
+    fn part1(blueprints: &Self::Input) -> usize {
+        quality_levels(blueprints, &Checkpoint::off())
+    }
+
+    fn part2(blueprints: &Self::Input) -> usize {
+        geode_product(blueprints, &Checkpoint::off())
+    }
+}
+
+impl Resume for Day19 {
+    fn resume(blueprints: &Self::Input, part: u8, checkpoint: &Checkpoint) -> String {
+        match part {
+            1 => quality_levels(blueprints, checkpoint).to_string(),
+            _ => geode_product(blueprints, checkpoint).to_string(),
+        }
+    }
+}
+
+fn quality_levels(blueprints: &[Blueprint], checkpoint: &Checkpoint) -> usize {
+    max_geodes(blueprints, 24, checkpoint).iter().enumerate().map(|(i, r)| (i + 1) * *r as usize).sum::<usize>()
+}
+
+fn geode_product(blueprints: &[Blueprint], checkpoint: &Checkpoint) -> usize {
+    max_geodes(&blueprints[..blueprints.len().min(3)], 32, checkpoint).iter().fold(1, |acc, r| acc * *r as usize)
+}
 
-    println!("Part 2: {}", part_2);
-    println!("Time: {}ms", start.elapsed().as_millis());
+//Most geodes each blueprint can open in `minutes`, searched in parallel; every blueprint is a unit of the checkpoint
+fn max_geodes(blueprints: &[Blueprint], minutes: u32, checkpoint: &Checkpoint) -> Vec<u32> {
+    let mut results = blueprints.iter().enumerate().map(|(i, _)| checkpoint.get(i).map(|r| r as u32)).collect_vec();
+    let context = &logging::context();
+    rayon::scope(|s| {
+        for (i, result) in results.iter_mut().enumerate().filter(|(_, r)| r.is_none()) {
+            let blueprint = &blueprints[i];
+            s.spawn(move |_| context.run(|| {
+                let _span = logging::span(format!("blueprint{}", i + 1));
+                let geodes = blueprint.simulate(SimState::new(minutes, [1, 0, 0, 0], [0;4]));
+                checkpoint.complete(i, geodes as u64);
+                *result = Some(geodes);
+            }));
+        }
+    });
+    results.into_iter().map(Option::unwrap).collect()
+}
+
+impl Generate for Day19 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        //Prices in the ranges of the real inputs
+        (1..=size.max(1)).map(|id| {
+            let ore = [rng.range(2..=4), rng.range(2..=4), rng.range(2..=4), rng.range(2..=4)];
+            let (clay, obsidian) = (rng.range(5..=20), rng.range(5..=20));
+            format!("Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.\n",
+                    id, ore[0], ore[1], ore[2], clay, ore[3], obsidian)
+        }).collect()
+    }
 }
 
 pub struct Blueprint {
@@ -48,22 +81,23 @@ pub struct Blueprint {
 }
 
 impl FromStr for Blueprint {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.is_empty() {
-            return Err(());
-        }
-        let r_split = s.split("Each").skip(1).collect_vec();
+        let mut scan = Scanner::new(s);
+        scan.literal("Blueprint")?;
+        scan.integer::<u32>("a blueprint number")?;
+        scan.literal(":")?;
         let mut prices = [[0; 4]; 4];
-        let p_split = r_split[0].trim().split(' ').collect_vec();
-        prices[0] = [p_split[3].trim().parse().unwrap(), 0, 0, 0];
-        let p_split = r_split[1].trim().split(' ').collect_vec();
-        prices[1] = [p_split[3].trim().parse().unwrap(), 0, 0, 0];
-        let p_split = r_split[2].trim().split(' ').collect_vec();
-        prices[2] = [p_split[3].trim().parse().unwrap(), p_split[6].trim().parse().unwrap(), 0, 0];
-        let p_split = r_split[3].trim().split(' ').collect_vec();
-        prices[3] = [p_split[3].trim().parse().unwrap(), 0, p_split[6].trim().parse().unwrap(), 0];
+        for (robot, name) in RESOURCES.iter().enumerate() {
+            scan.literal(&format!("Each {} robot costs", name))?;
+            //Geodes are never spent
+            for (price, resource) in scan.separated("and", |scan| Ok((scan.integer("a price")?, scan.one_of(&RESOURCES[..3])?)))? {
+                prices[robot][RESOURCES.iter().position(|r| *r == resource).unwrap()] = price;
+            }
+            scan.literal(".")?;
+        }
+        scan.end()?;
 
         Ok(Blueprint { prices })
     }
@@ -76,9 +110,11 @@ impl Blueprint {
         let mut seen_states = FxHashMap::default();
         let mut states_stack = Vec::new();
         let mut max_geodes = 0;
+        let (mut n_expanded, mut n_bounded, mut n_seen) = (0, 0, 0);
         states_stack.push(start_state);
 
         while let Some(state) = states_stack.pop() {
+            n_expanded += 1;
             match state.time {
                 0 => max_geodes = max_geodes.max(state.resources[3]),
                 _ => {
@@ -91,6 +127,8 @@ impl Blueprint {
                                             //If we've seen this combination before, but with less time left, add it to the stack
                                             seen_states.insert((next_state.bots, next_state.resources), next_state.time);
                                             states_stack.push(next_state);
+                                        } else {
+                                            n_seen += 1;
                                         }
                                     },
                                     None => {
@@ -99,11 +137,15 @@ impl Blueprint {
                                         states_stack.push(next_state);
                                     }
                                 }
+                            } else {
+                                n_bounded += 1;
                             }
                         });
                 }
             }
         }
+        log::debug!("{} geodes after expanding {} states, pruning {} by their geode bound and {} seen with more time left",
+            max_geodes, n_expanded, n_bounded, n_seen);
         max_geodes
     }
 
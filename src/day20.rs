@@ -1,28 +1,49 @@
 use std::collections::LinkedList;
 use std::rc::Rc;
 use std::str::FromStr;
-use std::time::Instant;
 use itertools::Itertools;
 
-const INPUT : &str = include_str!("../input/2022/day20.txt");
+use crate::{ParseError, Scanner, Solution};
+use crate::generate::{Generate, Rng};
 
-fn main(){
-    let start = Instant::now();
-    let encrypted_file = INPUT.parse::<EncryptedFile>().unwrap();
-    let part_1 = {
+pub struct Day20;
+
+const DECRYPTION_KEY: i64 = 811589153;
+
+impl Solution for Day20 {
+    type Input = EncryptedFile;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(encrypted_file: &Self::Input) -> i64 {
         let mut encrypted_file = encrypted_file.clone();
         encrypted_file.mix(1);
         encrypted_file.get(1000) + encrypted_file.get(2000) + encrypted_file.get(3000)
-    };
-    println!("Part 1: {}", part_1);
-    let part_2 = {
+    }
+
+    fn part2(encrypted_file: &Self::Input) -> i64 {
         let mut encrypted_file = encrypted_file.clone();
-        encrypted_file.key = 811589153;
+        encrypted_file.key = DECRYPTION_KEY;
         encrypted_file.mix(10);
         encrypted_file.get(1000) + encrypted_file.get(2000) + encrypted_file.get(3000)
-    };
-    println!("Part 2: {}", part_2);
-    println!("Time: {}ms", start.elapsed().as_millis());
+    }
+}
+
+impl Generate for Day20 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        //Exactly one zero, from which the grove coordinates are counted
+        let mut numbers = (1..size.max(2)).map(|_| match rng.range(-10_000..=10_000) {
+            0 => 1,
+            n => n,
+        }).collect_vec();
+        let at = rng.range(0..=numbers.len());
+        numbers.insert(at, 0);
+        numbers.iter().map(|n| format!("{}\n", n)).collect()
+    }
 }
 
 #[derive(Clone)]
@@ -32,29 +53,49 @@ pub struct EncryptedFile{
 }
 
 impl FromStr for EncryptedFile{
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let data = s.split('\n').filter_map(|line| line.parse::<i64>().ok()).map(Rc::new).collect();
+        let mut scan = Scanner::new(s);
+        let mut data = LinkedList::new();
+        //Numbers have to stay within 64 bits once multiplied by the key of part 2
+        let max = i64::MAX / DECRYPTION_KEY;
+        while !scan.is_empty() {
+            let start = scan;
+            match scan.integer::<i64>("a number")? {
+                n if (-max..=max).contains(&n) => data.push_back(Rc::new(n)),
+                _ => return Err(start.error(format!("a number from {} to {}", -max, max))),
+            }
+        }
+        //Mixing moves numbers among the others, and the grove coordinates are counted from 0
+        if data.len() < 2 {
+            return Err(ParseError::end(s, "at least two numbers"));
+        }
+        if !data.iter().any(|n| **n == 0) {
+            return Err(ParseError::end(s, "a 0"));
+        }
         Ok(EncryptedFile{
             data,
-            ..EncryptedFile::default()
+            ..Default::default()
         })
     }
 }
 
-impl EncryptedFile{
+impl Default for EncryptedFile{
     fn default() -> Self{
         EncryptedFile{data: LinkedList::new(), key: 1}
     }
+}
 
+impl EncryptedFile{
     fn mix(&mut self, times: i32) {
         let original_order = self.data.iter().cloned().collect_vec();
 
         for _ in 0..times {
             for value in original_order.iter() {
                 let position = self.data.iter().position(|x| Rc::ptr_eq(x, value)).unwrap();
-                let new_position = (position as i64 + (value.as_ref() * self.key)).rem_euclid(self.data.len() as i64 - 1) as usize;
+                let n_others = self.data.len() as i64 - 1;
+                let new_position = (position as i64 + (value.as_ref() * self.key) % n_others).rem_euclid(n_others) as usize;
                 let data = { //remove the value from old position
                     let mut split_list = self.data.split_off(position);
                     let data = split_list.pop_front().unwrap();
@@ -1,64 +1,207 @@
-extern crate core;
-
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::BuildHasherDefault;
+use std::str::FromStr;
 
 use fxhash::FxHasher;
 use itertools::Itertools;
 
-const INPUT: &str = include_str!("../input/2022/day21.txt");
+use crate::{ParseError, Scanner, Solution};
+use crate::generate::{Generate, Rng};
+use crate::solution::NoAnswer;
+
+pub struct Day21;
+
+const ROOT: [char; 4] = ['r', 'o', 'o', 't'];
+const HUMN: [char; 4] = ['h', 'u', 'm', 'n'];
+const ARITHMETIC: &str = "jobs that never divide by zero or leave 64 bit numbers";
+
+impl Solution for Day21 {
+    type Input = Statements;
+    type Part1 = Result<i64, NoAnswer>;
+    type Part2 = Result<i64, NoAnswer>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(statements: &Self::Input) -> Result<i64, NoAnswer> {
+        statements.clone().resolve(&ROOT).map_err(|_| NoAnswer(ARITHMETIC.to_string()))
+    }
 
-fn main() {
-    let start = std::time::Instant::now();
-    let statements = INPUT.lines().filter(|l| !l.is_empty())
-        .map(|l| {
-            let statement = Statement::from_str(l);
-            (statement.rhs().to_owned(), statement)
+    fn part2(statements: &Self::Input) -> Result<i64, NoAnswer> {
+        let mut statements_pt2 = statements.clone();
+        let equality = match statements_pt2.list.remove(&ROOT) {
+            Some(Statement::Op(_, a, _, b)) => (a, b),
+            _ => return Err(NoAnswer("an operation for root".to_string())),
+        };
+        if !matches!(statements_pt2.list.remove(&HUMN), Some(Statement::Number(..))) {
+            return Err(NoAnswer("a number for humn".to_string()));
         }
-        ).collect::<HashMap<[char;4], Statement, BuildHasherDefault<FxHasher>>>();
 
-    let statements = Statements {
-        list: statements
-    };
+        statements_pt2.resolve_advanced(&HUMN, &equality).map_err(|e| match e {
+            Unresolved::Missing(_) => NoAnswer("humn among the monkeys root waits for".to_string()),
+            Unresolved::Arithmetic => NoAnswer(ARITHMETIC.to_string()),
+        })
+    }
+}
 
-    println!("Part 1: {}", statements.clone().resolve(&['r','o','o','t']).unwrap());
+impl Generate for Day21 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        //Jobs are built down from the number every monkey has to yell once humn yells the answer to part 2,
+        //so that all divisions come out even
+        let mut jobs = vec![];
+        let mut names = HashSet::new();
+        let value = rng.range(1..=1000);
+        let depth = rng.range(1..=size.clamp(1, 12));
+        let humn_side = humn_job(rng, value, depth, &mut jobs, &mut names);
+        let other_side = constant_job(rng, value, (usize::BITS - size.leading_zeros()) as usize, &mut jobs, &mut names);
+        let (a, b) = match rng.chance(0.5) {
+            true => (humn_side, other_side),
+            false => (other_side, humn_side),
+        };
+        jobs.push(format!("root: {} + {}", a, b));
+        rng.shuffle(&mut jobs);
+        jobs.iter().map(|job| format!("{}\n", job)).collect()
+    }
+}
+
+fn new_name(rng: &mut Rng, names: &mut HashSet<String>) -> String {
+    loop {
+        let name = (0..4).map(|_| rng.range(b'a'..=b'z') as char).collect::<String>();
+        if !["root", "humn", "dmmy"].contains(&name.as_str()) && names.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+//Monkey yelling `value` (at least 1), with jobs up to `depth` deep below it
+fn constant_job(rng: &mut Rng, value: i64, depth: usize, jobs: &mut Vec<String>, names: &mut HashSet<String>) -> String {
+    let name = new_name(rng, names);
+    let factors = [2, 3, 5, 7].into_iter().filter(|f| value % f == 0).collect_vec();
+    let (a, op, b) = match rng.range(0..=3) {
+        _ if depth == 0 => {
+            jobs.push(format!("{}: {}", name, value));
+            return name;
+        }
+        0 if value >= 2 => {
+            let a = rng.range(1..=value - 1);
+            (a, '+', value - a)
+        }
+        1 if !factors.is_empty() => {
+            let factor = *rng.pick(&factors);
+            (value / factor, '*', factor)
+        }
+        2 => {
+            let divisor = rng.range(1..=5);
+            (value * divisor, '/', divisor)
+        }
+        _ => {
+            let b = rng.range(1..=100);
+            (value + b, '-', b)
+        }
+    };
+    let (depth_a, depth_b) = (rng.range(0..=depth - 1), rng.range(0..=depth - 1));
+    let a = constant_job(rng, a, depth_a, jobs, names);
+    let b = constant_job(rng, b, depth_b, jobs, names);
+    jobs.push(format!("{}: {} {} {}", name, a, op, b));
+    name
+}
 
-    let mut statements_pt2 = statements.clone();
-    statements_pt2.list.remove(&['h','u','m','n']);
-    let root = statements_pt2.list.remove(&['r','o','o','t']).unwrap();
-    let equality = match root {
-        Statement::Op(_, a, _, b) => (a, b),
-        _ => panic!("root is not an op"),
+//Monkey yelling `value` once humn yells the answer to part 2, `depth` jobs above humn; humn is never a divisor
+fn humn_job(rng: &mut Rng, value: i64, depth: usize, jobs: &mut Vec<String>, names: &mut HashSet<String>) -> String {
+    if depth == 0 {
+        jobs.push(format!("humn: {}", rng.range(1..=1000)));
+        return "humn".to_string();
+    }
+    let name = new_name(rng, names);
+    let other = rng.range(1..=10);
+    let (humn_value, op, humn_first) = match rng.range(0..=3) {
+        0 if rng.chance(0.5) => (value + other, '-', true),
+        0 => (other - value, '-', false),
+        1 if value % other == 0 => (value / other, '*', rng.chance(0.5)),
+        2 if value.abs() < 1_000_000 => (value * other, '/', true),
+        _ => (value - other, '+', rng.chance(0.5)),
+    };
+    let humn_side = humn_job(rng, humn_value, depth - 1, jobs, names);
+    let other_depth = rng.range(0..=2);
+    let other_side = constant_job(rng, other, other_depth, jobs, names);
+    let (a, b) = match humn_first {
+        true => (humn_side, other_side),
+        false => (other_side, humn_side),
     };
+    jobs.push(format!("{}: {} {} {}", name, a, op, b));
+    name
+}
 
-    println!("Part 2: {}", statements_pt2.resolve_advanced(&['h','u','m','n'], &equality).unwrap());
-    println!("Time: {:?}", start.elapsed());
+fn parse(input: &str) -> Result<Statements, ParseError> {
+    let lines = input.lines().filter(|l| !l.is_empty())
+        .map(|l| Ok((l, Statement::from_str(l).map_err(|e| e.within(input, l))?)))
+        .collect::<Result<Vec<(&str, Statement)>, ParseError>>()?;
+    let mut statements: HashMap<[char;4], Statement, BuildHasherDefault<FxHasher>> = HashMap::default();
+    for (l, statement) in &lines {
+        if statements.insert(statement.rhs().to_owned(), statement.clone()).is_some() {
+            return Err(ParseError::at(input, l, "a monkey without a job yet"));
+        }
+    }
+
+    //Every monkey but root is waited for by at most one other, so the jobs make a tree under root,
+    //which part 1 resolves and part 2 turns around
+    let mut waited_for = HashSet::new();
+    for (l, statement) in &lines {
+        if let Statement::Op(_, a, _, b) = statement {
+            let operands = &l[l.find(':').unwrap() + 1..];
+            for (operand, at) in [(a, operands.find(&name(a))), (b, operands.rfind(&name(b)))] {
+                let found = &operands[at.unwrap()..at.unwrap() + name(operand).len()];
+                if !statements.contains_key(operand) {
+                    return Err(ParseError::at(input, found, "a monkey with a job"));
+                }
+                if *operand == ROOT || !waited_for.insert(*operand) {
+                    return Err(ParseError::at(input, found, "a monkey no other one waits for"));
+                }
+            }
+        }
+    }
+    if !statements.contains_key(&ROOT) {
+        return Err(ParseError::end(input, "a job for root"));
+    }
+
+    Ok(Statements {
+        list: statements
+    })
+}
+
+fn name(name: &[char; 4]) -> String {
+    name.iter().collect()
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum Statement {
+pub enum Statement {
     Number([char;4], i64),
     Op([char;4], [char;4], char, [char;4]),
 }
 
 #[derive(Debug, Clone)]
-struct Statements {
+pub struct Statements {
     list: HashMap<[char;4], Statement, BuildHasherDefault<FxHasher>>,
 }
 
+//Why a monkey's number is not known: a monkey without a job, or a job that cannot be done
 #[derive(Debug)]
-struct MissingRHS([char;4]);
+enum Unresolved {
+    Missing([char;4]),
+    Arithmetic,
+}
 
 impl Statements {
-    fn resolve(&mut self, name: &[char;4]) -> Result<i64, MissingRHS> {
+    fn resolve(&mut self, name: &[char;4]) -> Result<i64, Unresolved> {
         match self.list.get(name) {
-            None => Err(MissingRHS(name.to_owned())),
+            None => Err(Unresolved::Missing(name.to_owned())),
             Some(Statement::Number(_, n)) => Ok(*n),
             Some(Statement::Op(_, a, op, b)) => {
-                let (a,op,b) = (a.clone(),*op,b.clone());
+                let (a,op,b) = (*a,*op,*b);
                 let res_a =self.resolve(&a)?;
                 let res_b = self.resolve(&b)?;
-                let res = do_op(res_a, op, res_b);
+                let res = do_op(res_a, op, res_b).ok_or(Unresolved::Arithmetic)?;
 
                 //replace the statement with the result
                 self.list.insert(name.to_owned(), Statement::Number(name.to_owned(), res));
@@ -68,11 +211,11 @@ impl Statements {
         }
     }
 
-    fn resolve_advanced(&mut self, name: &[char;4], equality: &([char;4], [char;4])) -> Result<i64, MissingRHS> {
+    fn resolve_advanced(&mut self, name: &[char;4], equality: &([char;4], [char;4])) -> Result<i64, Unresolved> {
         let mut modified_statements = vec![];
 
         let mut to_resolve = name.to_owned();
-        while let Err(MissingRHS(missing_rhs)) = self.resolve(&to_resolve) {
+        while let Err(Unresolved::Missing(missing_rhs)) = self.resolve(&to_resolve) {
             //if the missing rhs is one of the equality, add the equality statement
             match (missing_rhs == equality.0, missing_rhs == equality.1) {
                 (true, false) => {
@@ -92,14 +235,15 @@ impl Statements {
             //search for a statement which has not been modified before and where the lhs contains the missing operand
             let statement_to_mod = self.list.iter().find(|(_, s)|
                 !modified_statements.contains(*s) && s.contains_operand(&missing_rhs))
-                .ok_or_else(|| MissingRHS(missing_rhs.clone()))?.1;
+                .ok_or(Unresolved::Missing(missing_rhs))?.1;
 
             //modify the statement so the missing operand is the rhs
             let mod_statement = statement_to_mod.change_rhs(&missing_rhs);
 
             //update datastructures
             modified_statements.push(mod_statement.clone());
-            self.list.remove(&statement_to_mod.rhs().to_owned());
+            let old_rhs = *statement_to_mod.rhs();
+            self.list.remove(&old_rhs);
             self.list.insert(mod_statement.rhs().to_owned(), mod_statement);
 
             to_resolve = missing_rhs;
@@ -109,12 +253,12 @@ impl Statements {
     }
 }
 
-fn do_op(a: i64, op: char, b: i64) -> i64 {
+fn do_op(a: i64, op: char, b: i64) -> Option<i64> {
     match op {
-        '+' => a + b,
-        '-' => a - b,
-        '*' => a * b,
-        '/' => a / b,
+        '+' => a.checked_add(b),
+        '-' => a.checked_sub(b),
+        '*' => a.checked_mul(b),
+        '/' => a.checked_div(b),
         _ => panic!("Unknown operator {}", op)
     }
 }
@@ -173,17 +317,27 @@ impl Statement {
             _ => panic!("Can't flip a number"),
         }
     }
+}
 
-    fn from_str(s: &str) -> Statement {
-        let rhs = s.split(':').next().unwrap().chars().collect_vec().try_into().unwrap();
-        match s.split(':').nth(1).unwrap().trim().parse() {
-            Ok(n) => Statement::Number(rhs, n),
-            Err(_) => {
-                let a = s.trim().split(' ').nth(1).unwrap().chars().collect_vec().try_into().unwrap();
-                let op = s.trim().split(' ').nth(2).unwrap().chars().next().unwrap();
-                let b = s.trim().split(' ').nth(3).unwrap().chars().collect_vec().try_into().unwrap();
-                Statement::Op(rhs, a, op, b)
-            }
+impl FromStr for Statement {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = |word: &str| -> Result<[char; 4], ParseError> {
+            word.chars().collect_vec().try_into().map_err(|_| ParseError::at(s, word, "a four letter monkey name"))
+        };
+        let mut scan = Scanner::new(s);
+        let rhs = name(scan.identifier("a monkey name")?)?;
+        scan.literal(":")?;
+        let mut number = scan;
+        if let Ok(n) = number.integer("a number") {
+            number.end()?;
+            return Ok(Statement::Number(rhs, n));
         }
+        let a = name(scan.identifier("a number or monkey name")?)?;
+        let op = scan.one_of(&["+", "-", "*", "/"])?.chars().next().unwrap();
+        let b = name(scan.identifier("a monkey name")?)?;
+        scan.end()?;
+        Ok(Statement::Op(rhs, a, op, b))
     }
 }
\ No newline at end of file
@@ -1,50 +1,99 @@
 use std::str::FromStr;
 
 use itertools::Itertools;
-use scan_fmt::scan_fmt;
 
-const INPUT: &str = include_str!("../input/2022/day22.txt");
+use crate::day22_2::{self, Cube};
+use crate::{sections, Dir4, ParseError, Scanner, Solution, Turn};
+use crate::generate::{Generate, Rng};
 
-fn main() {
-    let maze = INPUT.split("\n\n").next().unwrap().parse::<Maze>().unwrap();
-    let instructions = parse_instructions(INPUT.split("\n\n").last().unwrap());
+pub struct Day22;
 
-    println!("Part 1: {}", simulate(&maze, &instructions));
+impl Solution for Day22 {
+    type Input = Notes;
+    type Part1 = usize;
+    type Part2 = Result<usize, ParseError>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let [map, path] = sections(input)[..] else {
+            return Err(ParseError::end(input, "a blank line followed by the path"));
+        };
+        let maze = map.parse::<Maze>()?;
+        //Only part 2 folds the map, so a map that does not fold into a known net still has an answer to part 1
+        let cube = map.parse::<Cube>().map_err(|e| e.within(input, map));
+        let instructions = parse_instructions(path).map_err(|e| e.within(input, path))?;
+
+        Ok(Notes { maze, cube, instructions })
+    }
+
+    fn part1(notes: &Self::Input) -> usize {
+        simulate(&notes.maze, &notes.instructions)
+    }
+
+    fn part2(notes: &Self::Input) -> Result<usize, ParseError> {
+        let cube = notes.cube.as_ref().map_err(Clone::clone)?;
+        Ok(day22_2::simulate(cube, &notes.instructions))
+    }
 }
 
-fn simulate(maze: &Maze, instructions: &Vec<Instr>) -> usize {
-    let mut orient = Orient::Right;
+impl Generate for Day22 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        //The squares lie like in the real inputs (rows of squares at these positions), with walls strewn over them
+        const SQUARES: [&[usize]; 4] = [&[1, 2], &[1], &[0, 1], &[0]];
+        let dim = size.max(2);
+        let mut notes = String::new();
+        for y in 0..4 * dim {
+            let squares = SQUARES[y / dim];
+            notes.push_str(&" ".repeat(squares[0] * dim));
+            //Both simulations start on the first tile, which has to be open
+            notes.extend((0..squares.len() * dim).map(|x| match (x, y) != (0, 0) && rng.chance(0.1) {
+                true => '#',
+                false => '.',
+            }));
+            notes.push('\n');
+        }
+        notes.push('\n');
+        (0..size.max(1)).for_each(|_| notes.push_str(&format!("{}{}", rng.range(1..=2 * dim), rng.pick(&['L', 'R']))));
+        notes + &format!("{}\n", rng.range(1..=dim))
+    }
+}
+
+//The map (flat and folded into a cube) and the path to follow
+pub struct Notes {
+    maze: Maze,
+    cube: Result<Cube, ParseError>,
+    instructions: Vec<Instr>,
+}
+
+fn simulate(maze: &Maze, instructions: &[Instr]) -> usize {
+    let mut orient = Dir4::Right;
     let mut pos = maze.start();
 
     for instr in instructions.iter() {
-        dbg!(instr);
         match instr {
-            Instr::Turn(rot) => {
-                orient = orient.turn(rot);
-                dbg!(&orient);
+            Instr::Turn(turn) => {
+                orient = orient.turn(*turn);
             },
             Instr::Move(n) => {
                 for _ in 0..*n {
-                    let new_pos = maze.go(pos, &orient);
+                    let new_pos = maze.go(pos, orient);
                     if new_pos == pos {
                         break;
                     }
                     pos = new_pos;
-                    dbg!(pos);
                 }
             }
         }
     }
     let facing = match orient{
-        Orient::Right => 0,
-        Orient::Down => 1,
-        Orient::Left => 2,
-        Orient::Up => 3,
+        Dir4::Right => 0,
+        Dir4::Down => 1,
+        Dir4::Left => 2,
+        Dir4::Up => 3,
     };
     1000 * (pos.0 + 1) + 4 * (pos.1 + 1) + facing
 }
 
-struct Maze {
+pub struct Maze {
     rows: Vec<Row>,
 }
 
@@ -53,12 +102,12 @@ impl Maze {
         (0, self.rows[0].offset)
     }
 
-    fn go(&self, pos: (usize, usize), orient: &Orient) -> (usize, usize) {
+    fn go(&self, pos: (usize, usize), orient: Dir4) -> (usize, usize) {
         match orient {
-            Orient::Up => (self.go_up(pos), pos.1),
-            Orient::Down => (self.go_down(pos), pos.1),
-            Orient::Left => (pos.0, self.rows[pos.0].go_left(pos.1).unwrap()),
-            Orient::Right => (pos.0, self.rows[pos.0].go_right(pos.1).unwrap()),
+            Dir4::Up => (self.go_up(pos), pos.1),
+            Dir4::Down => (self.go_down(pos), pos.1),
+            Dir4::Left => (pos.0, self.rows[pos.0].go_left(pos.1).unwrap()),
+            Dir4::Right => (pos.0, self.rows[pos.0].go_right(pos.1).unwrap()),
         }
     }
 
@@ -138,20 +187,25 @@ impl Row {
 }
 
 impl FromStr for Maze {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let rows = s.lines().filter_map(|line| line.parse::<Row>().ok()).collect_vec();
+        let rows = s.lines().filter(|line| !line.is_empty())
+            .map(|line| line.parse::<Row>().map_err(|e| e.within(s, line)))
+            .collect::<Result<Vec<Row>, ParseError>>()?;
         Ok(Maze { rows })
     }
 }
 
 impl FromStr for Row {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if s.is_empty() {
-            return Err(());
+            return Err(ParseError::end(s, "a row of the map"));
+        }
+        if let Some((i, c)) = s.char_indices().find(|(_, c)| !matches!(c, ' ' | '.' | '#')) {
+            return Err(ParseError::at(s, &s[i..i + c.len_utf8()], "an open tile, a wall or a space"));
         }
         let offset = s.len() - s.trim().len();
         let walls = s.trim().chars().map(|c| c == '#').collect_vec();
@@ -161,75 +215,37 @@ impl FromStr for Row {
 }
 
 #[derive(Debug)]
-enum Instr {
-    Turn(Rot),
+pub enum Instr {
+    Turn(Turn),
     Move(usize),
 }
 
-fn parse_instructions(s: &str) -> Vec<Instr> {
-    let mut remaining = s.trim().to_owned();
-    let mut instructions = Vec::new();
-    while !remaining.is_empty() {
-        match scan_fmt!(&remaining, "{}R{}", usize, String){
-            Ok((n,  s)) => {
-                instructions.push(Instr::Move(n));
-                instructions.push(Instr::Turn(Rot::Cw));
-                remaining = s;
-            },
-            Err(_) => {
-                match scan_fmt!(&remaining, "{}L{}", usize, String){
-                    Ok((n, s)) => {
-                        instructions.push(Instr::Move(n));
-                        instructions.push(Instr::Turn(Rot::Ccw));
-                        remaining = s;
-                    },
-                    Err(_) => {
-                        match scan_fmt!(&remaining, "{}", usize){
-                            Ok(n) => {
-                                instructions.push(Instr::Move(n));
-                                remaining = "".to_owned();
-                            },
-                            Err(_) => panic!("could not parse {}", remaining)
-                        }
-                    }
-                }
-            }
-        }
+pub fn parse_instructions(s: &str) -> Result<Vec<Instr>, ParseError> {
+    let mut scan = Scanner::new(s);
+    let mut instructions = vec![Instr::Move(scan.integer("a number of steps")?)];
+    while !scan.is_empty() {
+        let turn = match scan.one_of(&["R", "L"])? {
+            "R" => Turn::Right,
+            _ => Turn::Left,
+        };
+        instructions.push(Instr::Turn(turn));
+        instructions.push(Instr::Move(scan.integer("a number of steps")?));
     }
-    instructions
+    Ok(instructions)
 }
 
-#[derive(Debug)]
-enum Rot {
-    Cw,
-    Ccw,
-}
 
-#[derive(Debug)]
-enum Orient {
-    Up,
-    Down,
-    Left,
-    Right,
-}
 
-impl Orient {
-    fn turn(&self, turn: &Rot) -> Self {
-        match turn {
-            Rot::Cw => match self {
-                Orient::Up => Orient::Right,
-                Orient::Right => Orient::Down,
-                Orient::Down => Orient::Left,
-                Orient::Left => Orient::Up
-            },
-            Rot::Ccw => match self {
-                Orient::Up => Orient::Left,
-                Orient::Left => Orient::Down,
-                Orient::Down => Orient::Right,
-                Orient::Right => Orient::Up
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use crate::solution::solve;
+    use super::Day22;
+
+    #[test]
+    fn maps_that_do_not_fold_still_have_a_flat_answer() {
+        let err = solve::<Day22>("...\n.#.\n\n2R1\n", Some(2)).unwrap_err();
+        assert_eq!((err.line, err.expected.as_str()), (1, "a map folding into a known cube net"));
+        let (answers, _) = solve::<Day22>("...\n.#.\n\n2R1\n", Some(1)).unwrap();
+        assert_eq!(answers.part_1.as_deref(), Some("2013"));
     }
 }
-
-
@@ -2,40 +2,49 @@ use std::str::FromStr;
 
 use itertools::Itertools;
 use num::integer::Roots;
-use scan_fmt::scan_fmt;
 
-use crate::CubeSide::{Bottom, East, North, South, Top, West};
-use crate::Orient::{Down, Left, Right, Up};
+use crate::day22::Instr;
+use crate::{Dir4, ParseError};
 
-const INPUT: &str = include_str!("../input/2022/day22.txt");
-const SIDE_LOCATIONS: [(usize, usize); 6] = [(1, 1), (0, 3), (1, 0), (2, 0), (1, 2), (0, 2)];
+use self::CubeSide::{Bottom, East, North, South, Top, West};
+use crate::Dir4::{Down, Left, Right, Up};
 
-//HARD CODED FOR REAL INPUT
+//The two cube nets this solver knows how to fold
 
-//      +---+---+
-//      | N | E |
-//      +---+---+
-//      | T |
-//  +---+---+
-//  | W | S |
-//  +---+---+
+//Real input:       Example input:
+//      +---+---+           +---+
+//      | N | E |           | N |
+//      +---+---+   +---+---+---+
+//      | T |       | B | W | T |
+//  +---+---+       +---+---+---+---+
+//  | W | S |               | S | E |
+//  +---+---+               +---+---+
 //  | B |
 //  +---+
 
-fn main() {
-    let cube = INPUT.split("\n\n").next().unwrap().parse::<Cube>().unwrap();
-    let instructions = parse_instructions(INPUT.split("\n\n").last().unwrap());
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Net {
+    Input,
+    Example,
+}
 
-    println!("Part 2: {}", simulate(&cube, &instructions));
+impl Net {
+    //(column, row) of the Top, Bottom, North, East, South and West squares in the map
+    fn side_locations(&self) -> [(usize, usize); 6] {
+        match self {
+            Net::Input => [(1, 1), (0, 3), (1, 0), (2, 0), (1, 2), (0, 2)],
+            Net::Example => [(2, 1), (0, 1), (2, 0), (3, 2), (2, 2), (1, 1)],
+        }
+    }
 }
 
-fn simulate(cube: &Cube, instructions: &Vec<Instr>) -> usize {
+pub fn simulate(cube: &Cube, instructions: &[Instr]) -> usize {
     let (mut side, mut row, mut col, mut orient) = cube.start();
 
     for instr in instructions.iter() {
         match instr {
-            Instr::Turn(rot) => {
-                orient = orient.turn(rot);
+            Instr::Turn(turn) => {
+                orient = orient.turn(*turn);
             }
             Instr::Move(n) => {
                 for _ in 0..*n {
@@ -49,14 +58,7 @@ fn simulate(cube: &Cube, instructions: &Vec<Instr>) -> usize {
         }
     }
     let dim = cube.dim;
-    let (col_extra, row_extra) = match side {
-        Top => SIDE_LOCATIONS[0],
-        Bottom => SIDE_LOCATIONS[1],
-        North => SIDE_LOCATIONS[2],
-        East => SIDE_LOCATIONS[3],
-        South => SIDE_LOCATIONS[4],
-        West => SIDE_LOCATIONS[5],
-    };
+    let (col_extra, row_extra) = cube.net.side_locations()[side as usize];
 
     let (row, col) = (row + row_extra * dim, col + col_extra * dim);
 
@@ -71,17 +73,18 @@ fn simulate(cube: &Cube, instructions: &Vec<Instr>) -> usize {
 
 struct OutOfBoundsErr;
 
-struct Cube {
+pub struct Cube {
+    net: Net,
     dim: usize,
     sides: Vec<Square>
 }
 
 impl Cube {
-    fn start(&self) -> (CubeSide, usize, usize, Orient) {
+    fn start(&self) -> (CubeSide, usize, usize, Dir4) {
         (North, 0, 0, Right)
     }
 
-    fn go(&self, (side, row, col, orient): (CubeSide, usize, usize, Orient)) -> (CubeSide, usize, usize, Orient) {
+    fn go(&self, (side, row, col, orient): (CubeSide, usize, usize, Dir4)) -> (CubeSide, usize, usize, Dir4) {
         match self.sides[side as usize].go((row, col), orient) {
             Ok((n_row, n_col)) => {
                 match self.sides[side as usize].is_wall((n_row, n_col)) {
@@ -99,9 +102,16 @@ impl Cube {
         }
     }
 
-    fn switch_sides(&self, (side, row, col, orient): (CubeSide, usize, usize, Orient)) -> (CubeSide, usize, usize, Orient) {
+    fn switch_sides(&self, pos: (CubeSide, usize, usize, Dir4)) -> (CubeSide, usize, usize, Dir4) {
+        match self.net {
+            Net::Input => self.switch_sides_input(pos),
+            Net::Example => self.switch_sides_example(pos),
+        }
+    }
+
+    fn switch_sides_input(&self, (side, row, col, orient): (CubeSide, usize, usize, Dir4)) -> (CubeSide, usize, usize, Dir4) {
         let last = self.dim - 1;
-        let (flipped_row, flipped_col) = (last - row, last - col);
+        let flipped_row = last - row;
         match side {
             Top => {
                 match orient {
@@ -153,6 +163,61 @@ impl Cube {
             }
         }
     }
+
+    fn switch_sides_example(&self, (side, row, col, orient): (CubeSide, usize, usize, Dir4)) -> (CubeSide, usize, usize, Dir4) {
+        let last = self.dim - 1;
+        let (flipped_row, flipped_col) = (last - row, last - col);
+        match side {
+            Top => {
+                match orient {
+                    Up => (North, last, col, Up),
+                    Down => (South, 0, col, Down),
+                    Left => (West, row, last, Left),
+                    Right => (East, 0, flipped_row, Down),
+                }
+            }
+            Bottom => {
+                match orient {
+                    Up => (North, 0, flipped_col, Down),
+                    Down => (South, last, flipped_col, Up),
+                    Left => (East, last, flipped_row, Up),
+                    Right => (West, row, 0, Right),
+                }
+            }
+            North => {
+                match orient {
+                    Up => (Bottom, 0, flipped_col, Down),
+                    Down => (Top, 0, col, Down),
+                    Left => (West, 0, row, Down),
+                    Right => (East, flipped_row, last, Left),
+                }
+            }
+            East => {
+                match orient {
+                    Up => (Top, flipped_col, last, Left),
+                    Down => (Bottom, flipped_col, 0, Right),
+                    Left => (South, row, last, Left),
+                    Right => (North, flipped_row, last, Left),
+                }
+            }
+            South => {
+                match orient {
+                    Up => (Top, last, col, Up),
+                    Down => (Bottom, last, flipped_col, Up),
+                    Left => (West, last, flipped_row, Up),
+                    Right => (East, row, 0, Right),
+                }
+            }
+            West => {
+                match orient {
+                    Up => (North, col, 0, Right),
+                    Down => (South, flipped_col, 0, Right),
+                    Left => (Bottom, row, last, Left),
+                    Right => (Top, row, 0, Right),
+                }
+            }
+        }
+    }
 }
 
 struct Square {
@@ -161,7 +226,7 @@ struct Square {
 }
 
 impl Square {
-    fn go(&self, from: (usize, usize), orient: Orient) -> Result<(usize, usize), OutOfBoundsErr> {
+    fn go(&self, from: (usize, usize), orient: Dir4) -> Result<(usize, usize), OutOfBoundsErr> {
         let to = match orient {
             Up => (from.0.checked_sub(1).ok_or(OutOfBoundsErr)?, from.1),
             Down => (from.0 + 1, from.1),
@@ -181,27 +246,33 @@ impl Square {
 }
 
 impl FromStr for Cube {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let lines = s.lines().collect_vec();
         let dim = (lines.iter().map(|l| l.trim().len()).sum::<usize>() / 6).sqrt();
 
-        let sides = SIDE_LOCATIONS.iter().map(|(x, y)| {
-            let x_range = (x * dim)..((x + 1) * dim);
-            let y_range = (y * dim)..((y + 1) * dim);
-            let square = y_range.map(|i| &lines[i][x_range.clone()]).collect_vec();
-            parse_square(&square)
-        }).collect_vec();
+        //Rows of the square at (x, y), if it lies on the map in full
+        let square = |&(x, y): &(usize, usize)| (y * dim..(y + 1) * dim)
+            .map(|i| lines.get(i).and_then(|l| l.get(x * dim..(x + 1) * dim)).filter(|row| !row.contains(' ')))
+            .collect::<Option<Vec<&str>>>()
+            .filter(|_| dim > 0);
+
+        //Pick the net whose squares all lie on the map
+        let (net, squares) = [Net::Input, Net::Example].into_iter()
+            .find_map(|net| net.side_locations().iter().map(square).collect::<Option<Vec<_>>>().map(|squares| (net, squares)))
+            .ok_or_else(|| ParseError::at(s, lines.first().copied().unwrap_or_default(), "a map folding into a known cube net"))?;
+        let sides = squares.iter().map(|square| parse_square(square)).collect_vec();
 
         Ok(Cube {
+            net,
             dim,
             sides
         })
     }
 }
 
-fn parse_square(lines: &Vec<&str>) -> Square {
+fn parse_square(lines: &[&str]) -> Square {
     let dim = lines[0].trim().len();
     let rows = lines.iter().map(|line| {
         line.trim().chars().map(|c| c == '#').collect_vec()
@@ -213,78 +284,6 @@ fn parse_square(lines: &Vec<&str>) -> Square {
     }
 }
 
-#[derive(Debug)]
-enum Instr {
-    Turn(Rot),
-    Move(usize),
-}
-
-fn parse_instructions(s: &str) -> Vec<Instr> {
-    let mut remaining = s.trim().to_owned();
-    let mut instructions = Vec::new();
-    while !remaining.is_empty() {
-        match scan_fmt!(&remaining, "{}R{}", usize, String) {
-            Ok((n, s)) => {
-                instructions.push(Instr::Move(n));
-                instructions.push(Instr::Turn(Rot::Cw));
-                remaining = s;
-            }
-            Err(_) => {
-                match scan_fmt!(&remaining, "{}L{}", usize, String) {
-                    Ok((n, s)) => {
-                        instructions.push(Instr::Move(n));
-                        instructions.push(Instr::Turn(Rot::Ccw));
-                        remaining = s;
-                    }
-                    Err(_) => {
-                        match scan_fmt!(&remaining, "{}", usize) {
-                            Ok(n) => {
-                                instructions.push(Instr::Move(n));
-                                remaining = "".to_owned();
-                            }
-                            Err(_) => panic!("could not parse {}", remaining)
-                        }
-                    }
-                }
-            }
-        }
-    }
-    instructions
-}
-
-#[derive(Debug)]
-enum Rot {
-    Cw,
-    Ccw,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Orient {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Orient {
-    fn turn(&self, turn: &Rot) -> Self {
-        match turn {
-            Rot::Cw => match self {
-                Up => Right,
-                Right => Down,
-                Down => Left,
-                Left => Up
-            },
-            Rot::Ccw => match self {
-                Up => Left,
-                Left => Down,
-                Down => Right,
-                Right => Up
-            }
-        }
-    }
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum CubeSide {
     Top,
@@ -303,40 +302,37 @@ enum CubeSide {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Cube, INPUT};
-    use crate::CubeSide::{Bottom, East, North, South, Top, West};
-    use crate::Orient::{Down, Left, Right, Up};
+    use crate::input::{self, InputSource};
+    use super::{Cube, Net};
+    use super::CubeSide::{Bottom, East, North, South, Top, West};
+    use crate::Dir4::{self, Down, Left, Right, Up};
 
     #[test]
     fn transformation_table_is_reversible() {
-        let cube = INPUT.split("\n\n").next().unwrap().parse::<Cube>().unwrap();
+        for (source, net) in [(InputSource::Default, Net::Input), (InputSource::Variant("test".to_string()), Net::Example)] {
+            let input = input::load(2022, 22, &source).unwrap();
+            let cube = input.split("\n\n").next().unwrap().parse::<Cube>().unwrap();
+            assert_eq!(cube.net, net);
+            check_reversible(&cube);
+        }
+    }
 
+    fn check_reversible(cube: &Cube) {
         let sides = [Top, Bottom, North, East, South, West];
-        let flip_orient = |o| {
-            match o {
-                Up => Down,
-                Down => Up,
-                Left => Right,
-                Right => Left,
-            }
-        };
+        let flip_orient = |o: Dir4| o.reverse();
 
         let last = cube.dim - 1;
 
         for side in sides {
             let checks = [(side, 0,1, Up), (side, last, 1, Down), (side, 1, 0, Left), (side, 1, last, Right)];
 
-            for side in sides {
-                let checks = [(side, 0,1, Up), (side, last, 1, Down), (side, 1, 0, Left), (side, 1, last, Right)];
-
-                for check in checks {
-                    let old_pos = check;
-                    assert!(cube.sides[old_pos.0 as usize].go((old_pos.1, old_pos.2), old_pos.3).is_err());
-                    let new_pos = cube.switch_sides(old_pos);
-                    assert!(cube.sides[new_pos.0 as usize].go((new_pos.1, new_pos.2), flip_orient(new_pos.3)).is_err());
-                    let should_be_old_pos = cube.switch_sides((new_pos.0, new_pos.1, new_pos.2, flip_orient(new_pos.3)));
-                    assert_eq!((old_pos.0, old_pos.1, old_pos.2, flip_orient(old_pos.3)), should_be_old_pos);
-                }
+            for check in checks {
+                let old_pos = check;
+                assert!(cube.sides[old_pos.0 as usize].go((old_pos.1, old_pos.2), old_pos.3).is_err());
+                let new_pos = cube.switch_sides(old_pos);
+                assert!(cube.sides[new_pos.0 as usize].go((new_pos.1, new_pos.2), flip_orient(new_pos.3)).is_err());
+                let should_be_old_pos = cube.switch_sides((new_pos.0, new_pos.1, new_pos.2, flip_orient(new_pos.3)));
+                assert_eq!((old_pos.0, old_pos.1, old_pos.2, flip_orient(old_pos.3)), should_be_old_pos);
             }
         }
     }
@@ -1,128 +1,178 @@
-use std::collections::{HashMap};
-use std::time::Instant;
 use itertools::Itertools;
-use crate::Direction::{N, S, E, W, NE, NW, SE, SW};
-
-const INPUT: &str = include_str!("../input/2022/day23.txt");
-
-pub fn main(){
-    let start = Instant::now();
-    let mut elves = parse(INPUT);
-    elves.simulate(10);
-    let part_1 = elves.empty_tiles_in_bbox();
-    println!("Part 1: {}", part_1);
-    elves.simulate(u32::MAX as usize);
-    let part_2 = elves.iterations;
-    println!("Part 2: {}", part_2);
-    println!("Time: {:?}", start.elapsed());
+use crate::Dir8::{self, N, S, E, W, NE, NW, SE, SW};
+use crate::{Grid, ParseError, Point2, Solution};
+use crate::visualize::{Frame, Recorder, Visualize};
+use crate::generate::{Generate, Rng};
+
+//Free tiles added around the map whenever an elf reaches its edge
+const MARGIN: i32 = 10;
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Elves;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(elves: &Self::Input) -> usize {
+        empty_tiles_after_10_rounds(elves, &mut Recorder::off())
+    }
+
+    fn part2(elves: &Self::Input) -> usize {
+        rounds_until_settled(elves, &mut Recorder::off())
+    }
+}
+
+impl Generate for Day23 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        //Half of the ground is taken by elves, and there is at least one in the middle
+        let n = size.max(1);
+        (0..n).map(|y| (0..n).map(|x| match (x, y) == (n / 2, n / 2) || rng.chance(0.5) {
+            true => '#',
+            false => '.',
+        }).chain(['\n']).collect::<String>()).collect()
+    }
+}
+
+impl Visualize for Day23 {
+    fn visualize(elves: &Self::Input, part: u8, recorder: &mut Recorder) {
+        match part {
+            1 => { empty_tiles_after_10_rounds(elves, recorder); },
+            _ => { rounds_until_settled(elves, recorder); },
+        }
+    }
+}
+
+fn empty_tiles_after_10_rounds(elves: &Elves, recorder: &mut Recorder) -> usize {
+    let mut elves = elves.clone();
+    elves.simulate(10, recorder);
+    elves.empty_tiles_in_bbox()
+}
+
+fn rounds_until_settled(elves: &Elves, recorder: &mut Recorder) -> usize {
+    let mut elves = elves.clone();
+    elves.simulate(u32::MAX as usize, recorder);
+    elves.iterations
 }
 
-struct Elves{
-    locations: HashMap<(i32,i32), Elf>,
+#[derive(Clone)]
+pub struct Elves{
+    map: Grid<bool>, //tiles taken by an elf
+    elves: Vec<Elf>,
     iterations: usize,
 }
 
 impl Elves {
-    fn simulate(&mut self, n_times: usize){
+    fn simulate(&mut self, n_times: usize, recorder: &mut Recorder){
         for i in self.iterations..(self.iterations + n_times) {
-            let proposals = self.locations.values()
-                .flat_map(|elf| elf.propose(&self.locations, i))
+            self.ensure_margin();
+            let proposals = self.elves.iter()
+                .map(|elf| elf.propose(&self.map, i))
                 .collect_vec();
 
-            let mut proposal_to_map = HashMap::new();
-            for Proposal(from, to) in proposals {
-                proposal_to_map.insert(to, match proposal_to_map.get(&to) {
-                    Some(_) => Err(ConflictingProposals),
-                    None => Ok(from),
-                });
+            let mut n_proposals = Grid::new(self.map.width(), self.map.height(), 0_u8);
+            for &to in proposals.iter().flatten() {
+                n_proposals[to] += 1;
             }
 
             let mut no_elves_moved = true;
-            for (to, from) in proposal_to_map {
-                if let Ok(from) = from {
+            for (elf, proposal) in self.elves.iter_mut().zip(proposals) {
+                //Elves only propose free tiles, so moving one never frees a tile another elf proposed
+                if let Some(to) = proposal.filter(|&to| n_proposals[to] == 1) {
                     no_elves_moved = false;
-                    let mut elf = self.locations.remove(&from).unwrap();
-                    elf.move_to(to);
-                    assert!(!self.locations.contains_key(&to));
-                    self.locations.insert(to, elf);
+                    self.map[elf.0] = false;
+                    self.map[to] = true;
+                    elf.0 = to;
                 }
             }
             self.iterations += 1;
+            recorder.frame(|| self.frame());
             if no_elves_moved {
                 break;
             }
         }
     }
 
+    //Grows the map once an elf reaches its edge, so all tiles around every elf are on the map
+    fn ensure_margin(&mut self) {
+        let (width, height) = (self.map.width() as i32, self.map.height() as i32);
+        if self.elves.iter().all(|Elf(p)| p.x > 0 && p.y > 0 && p.x < width - 1 && p.y < height - 1) {
+            return;
+        }
+        self.map = Grid::new((width + 2 * MARGIN) as usize, (height + 2 * MARGIN) as usize, false);
+        for elf in self.elves.iter_mut() {
+            elf.0 += Point2::new(MARGIN, MARGIN);
+            self.map[elf.0] = true;
+        }
+    }
+
     fn empty_tiles_in_bbox(&self) -> usize {
-        let (min_x, max_x, min_y, max_y) = self.locations.keys().fold(
-            (i32::MAX,i32::MIN,i32::MAX,i32::MIN), |(min_x, max_x, min_y, max_y), (x, y)| {
-            (min_x.min(*x), max_x.max(*x), min_y.min(*y), max_y.max(*y))
-        });
-        (min_x..=max_x).map(|x| (min_y..=max_y).filter(|&y| !self.locations.contains_key(&(x,y))).count()).sum()
+        let (min, max) = self.bbox();
+        ((max.x - min.x + 1) * (max.y - min.y + 1)) as usize - self.elves.len()
     }
-}
 
-struct Proposal((i32,i32), (i32,i32));
-struct ConflictingProposals;
+    //Top left and bottom right corner of the smallest rectangle holding every elf
+    fn bbox(&self) -> (Point2, Point2) {
+        let (min_x, max_x, min_y, max_y) = self.elves.iter().fold(
+            (i32::MAX,i32::MIN,i32::MAX,i32::MIN), |(min_x, max_x, min_y, max_y), Elf(p)| {
+            (min_x.min(p.x), max_x.max(p.x), min_y.min(p.y), max_y.max(p.y))
+        });
+        (Point2::new(min_x, min_y), Point2::new(max_x, max_y))
+    }
 
-struct Elf{
-    x : i32,
-    y : i32,
+    //The elves and the ground just around them
+    fn frame(&self) -> Frame {
+        let (min, max) = self.bbox();
+        let picture = (min.y - 1..=max.y + 1).map(|y| {
+            (min.x - 1..=max.x + 1).map(|x| if self.map.get(x, y) == Some(&true) { '#' } else { '.' }).collect::<String>()
+        }).join("\n");
+        Frame::new(format!("round {}", self.iterations), picture)
+    }
 }
 
+#[derive(Clone)]
+struct Elf(Point2);
+
 impl Elf{
-    fn propose(&self, others: &HashMap<(i32,i32), Elf>, iteration : usize) -> Option<Proposal> {
-        if [N,S,E,W,NE,NW,SE,SW].iter().map(|&d| self.step_in_dir(d)).all(|p| !others.contains_key(&p)) {
+    fn propose(&self, map: &Grid<bool>, iteration : usize) -> Option<Point2> {
+        let free = |p: Point2| map.get(p.x, p.y) != Some(&true);
+        if self.0.neighbors8().into_iter().all(free) {
             return None;
         }
-        const RULES: [([Direction; 3], Direction); 4] = [([N,NE,NW], N),([S,SE,SW], S),([W,NW,SW], W),([E,NE,SE], E)];
+        const RULES: [([Dir8; 3], Dir8); 4] = [([N,NE,NW], N),([S,SE,SW], S),([W,NW,SW], W),([E,NE,SE], E)];
 
         for i in 0..RULES.len() {
             let rule_index = (i + iteration) % RULES.len(); // rotate rules
             let (rule, dir) = &RULES[rule_index];
-            if rule.iter().map(|&d| self.step_in_dir(d)).all(|p| !others.contains_key(&p)) {
-                return Some(Proposal((self.x, self.y), self.step_in_dir(*dir)));
+            if rule.iter().map(|&d| self.step_in_dir(d)).all(free) {
+                return Some(self.step_in_dir(*dir));
             }
         }
         None
     }
 
-    fn step_in_dir(&self, dir: Direction) -> (i32, i32){
-        match dir{
-            N => (self.x, self.y-1),
-            S => (self.x, self.y+1),
-            W => (self.x-1, self.y),
-            E => (self.x+1, self.y),
-            NE => (self.x+1, self.y-1),
-            NW => (self.x-1, self.y-1),
-            SE => (self.x+1, self.y+1),
-            SW => (self.x-1, self.y+1),
-        }
-    }
-
-    fn move_to(&mut self, (x,y): (i32,i32)){
-        self.x = x;
-        self.y = y;
+    fn step_in_dir(&self, dir: Dir8) -> Point2 {
+        self.0 + dir.offset()
     }
 }
 
-#[derive(Clone, Copy)]
-enum Direction{
-    N, S, E, W, NE, NW, SE, SW
-}
-
-fn parse(input: &str) -> Elves{
-    let mut elves = Elves{
-        locations: HashMap::new(),
-        iterations: 0,
-    };
-    for (y,line) in input.lines().enumerate(){
-        for (x, c) in line.chars().enumerate(){
-            if c == '#'{
-                elves.locations.insert((x as i32, y as i32), Elf{x: x as i32, y: y as i32});
-            }
-        }
+fn parse(input: &str) -> Result<Elves, ParseError> {
+    let map = Grid::parse(input, |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    }, "an elf (#) or ground (.)")?;
+    let elves: Vec<Elf> = map.positions().filter(|&p| map[p]).map(|(x, y)| Elf(Point2::new(x as i32, y as i32))).collect();
+    if elves.is_empty() {
+        return Err(ParseError::end(input, "an elf (#)"));
     }
-    elves
+    Ok(Elves{
+        map,
+        elves,
+        iterations: 0,
+    })
 }
@@ -1,162 +1,172 @@
-use std::cmp::Reverse;
-use std::collections::{BinaryHeap, HashSet};
-use std::time::Instant;
-use itertools::Itertools;
-
-const INPUT: &str = include_str!("../input/2022/day24.txt");
-
-fn main() {
-    let start = Instant::now();
-    let valley = parse(INPUT);
-    let from: (i32, i32) = (0, -1);
-    let to: (i32, i32) = (valley.size.0 - 1, valley.size.1);
-    let part1 = valley.simulate(0, from, to);
-    println!("Part 1: {}", part1);
-
-    let part2 = {
-        let back = valley.simulate(part1, to, from);
-        valley.simulate(back, from, to)
-    };
-    println!("Part 2: {}", part2);
-    println!("Time: {:?}", start.elapsed());
-}
+use num::integer::lcm;
 
-struct Valley {
-    blizzards: Vec<Blizzard>,
-    column_blizzards : Vec<Vec<usize>>, //indices of blizzards traveling over each column
-    row_blizzards : Vec<Vec<usize>>, //indices of blizzards traveling over each row
-    size: (i32, i32)
-}
+use crate::{Dir4, Grid, ParseError, Point2, Solution};
+use crate::search;
+use crate::visualize::{Frame, Recorder, Visualize};
+use crate::generate::{Generate, Rng};
+use crate::solution::NoAnswer;
 
-impl Valley {
-    fn simulate(&self, time: i32, from: (i32, i32), to: (i32, i32)) -> i32 {
-        let mut queue = BinaryHeap::new();
-        let mut visited = HashSet::new();
-        let mut best_time = i32::MAX;
-        queue.push(Reverse((manhattan(from, to), from, time))); //Minheap on distance to exit
-
-        while let Some(Reverse((dist, pos, time))) = queue.pop() {
-            if dist > (best_time - time) {
-                continue; //Skip, above upperbound
-            }
-            if pos == to {
-                best_time = best_time.min(time); //arrived
-            }
+pub struct Day24;
 
-            //consider all neighboring states and add only the relevant ones to the queue
-            Self::get_neighbors(pos).into_iter()
-                .filter(|&p| self.pos_is_in_valley(p) || p == to || p == from) //position is inside valley
-                .filter(|&p| self.pos_is_blizzard_free(p, time + 1)) //no blizzard there
-                .for_each(|p| {
-                    if !visited.contains(&(p, time + 1)){
-                        //Only consider previously unvisited states
-                        visited.insert((p, time + 1));
-                        queue.push(Reverse((manhattan(p, to), p, time + 1)));
-                    }
-                });
-        }
-        best_time
+impl Solution for Day24 {
+    type Input = Valley;
+    type Part1 = Result<i32, NoAnswer>;
+    type Part2 = Result<i32, NoAnswer>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
     }
 
-    fn get_neighbors(pos: (i32,i32)) -> [(i32,i32); 5] {
-        [
-            (pos.0, pos.1), //stay
-            (pos.0 - 1, pos.1), //up
-            (pos.0 + 1, pos.1), //down
-            (pos.0, pos.1 - 1), //left
-            (pos.0, pos.1 + 1), //right
-        ]
+    fn part1(valley: &Self::Input) -> Result<i32, NoAnswer> {
+        crossings(valley, 1, &mut Recorder::off())
     }
 
-    fn pos_is_in_valley(&self, pos: (i32, i32)) -> bool {
-        pos.0 >= 0 && pos.0 < self.size.0 && pos.1 >= 0 && pos.1 < self.size.1
+    fn part2(valley: &Self::Input) -> Result<i32, NoAnswer> {
+        crossings(valley, 3, &mut Recorder::off())
     }
+}
 
-    fn pos_is_blizzard_free(&self, pos: (i32, i32), time: i32) -> bool{
-        match self.pos_is_in_valley(pos){
-            true => self.column_blizzards[pos.0 as usize].iter()
-                .chain(self.row_blizzards[pos.1 as usize].iter())
-                .map(|i| &self.blizzards[*i])
-                .all(|b| b.pos(time) != pos),
-            false => true
+impl Generate for Day24 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        //No blizzards go up or down the columns of the entrance and the exit, like in the real inputs
+        let n = size.max(3);
+        loop {
+            let mut notes = format!("#.{}\n", "#".repeat(n));
+            for _ in 0..n {
+                notes.push('#');
+                notes.extend((0..n).map(|x| match rng.chance(0.3) {
+                    false => '.',
+                    true if x == 0 || x == n - 1 => *rng.pick(&['<', '>']),
+                    true => *rng.pick(&['<', '>', '^', 'v']),
+                }));
+                notes.push_str("#\n");
+            }
+            notes += &format!("{}.#\n", "#".repeat(n));
+            //Small valleys can be blocked for good
+            let valley = parse(&notes).expect("generated valleys are valid");
+            let (entrance, exit) = valley.entrance_and_exit();
+            if valley.can_cross(entrance, exit) && valley.can_cross(exit, entrance) {
+                return notes;
+            }
         }
     }
 }
 
-struct Blizzard {
-    start_pos : (i32, i32),
-    valley_size: (i32, i32),
-    dir: Direction,
+impl Visualize for Day24 {
+    fn visualize(valley: &Self::Input, part: u8, recorder: &mut Recorder) {
+        let _ = match part {
+            1 => crossings(valley, 1, recorder),
+            _ => crossings(valley, 3, recorder),
+        };
+    }
 }
 
-impl Blizzard {
-    fn new(start_pos: (i32,i32), valley_size: (i32, i32), dir: Direction) -> Self {
-        Self {
-            start_pos,
-            valley_size,
-            dir
-        }
+//Time at which the expedition is done crossing the valley `n` times, going back and forth
+fn crossings(valley: &Valley, n: usize, recorder: &mut Recorder) -> Result<i32, NoAnswer> {
+    let (entrance, exit) = valley.entrance_and_exit();
+    (0..n).try_fold(0, |time, i| match i % 2 {
+        0 => valley.simulate(time, entrance, exit, recorder),
+        _ => valley.simulate(time, exit, entrance, recorder),
+    }.ok_or_else(|| NoAnswer("a valley that blizzards do not block for good".to_string())))
+}
+
+pub struct Valley {
+    blizzards: Grid<Option<Dir4>>, //blizzards at time 0, inside the walls
+    size: Point2,
+}
+
+impl Valley {
+    //Gaps in the walls above the top left and below the bottom right of the valley
+    fn entrance_and_exit(&self) -> (Point2, Point2) {
+        (Point2::new(0, -1), Point2::new(self.size.x - 1, self.size.y))
     }
 
-    fn pos(&self, time: i32) -> (i32,i32){
-        match &self.dir{
-            Direction::Up => (self.start_pos.0, (self.start_pos.1 - time).rem_euclid(self.valley_size.1)),
-            Direction::Down => (self.start_pos.0, (self.start_pos.1 + time).rem_euclid(self.valley_size.1)),
-            Direction::Left => ((self.start_pos.0 - time).rem_euclid(self.valley_size.0), self.start_pos.1),
-            Direction::Right => ((self.start_pos.0 + time).rem_euclid(self.valley_size.0), self.start_pos.1),
-        }
+    fn simulate(&self, time: i32, from: Point2, to: Point2, recorder: &mut Recorder) -> Option<i32> {
+        //A state is a position at some time in the cycle of the blizzards, every step (or wait) takes a minute.
+        //There are only so many of those, so the search ends even if `to` cannot be reached
+        let period = lcm(self.size.x, self.size.y);
+        let states = |&(pos, time): &(Point2, i32)| self.moves(pos, time, from, to).map(move |p| ((p, (time + 1) % period), 1));
+        //The distance to the exit is a lower bound on the time it still takes
+        let path = search::astar(&states, [(from, time % period)], |&(pos, _)| pos == to, |&(pos, _)| pos.manhattan(to) as usize)?;
+        path.nodes.iter().zip(time..).for_each(|(&(pos, _), time)| recorder.frame(|| self.frame(pos, time)));
+        Some(time + path.cost as i32)
     }
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+    //Positions the expedition can be at a minute after being at `pos`, on its way between `from` and `to`
+    fn moves(&self, pos: Point2, time: i32, from: Point2, to: Point2) -> impl Iterator<Item = Point2> + '_ {
+        pos.neighbors4().into_iter().chain([pos]) //move or stay
+            .filter(move |&p| self.pos_is_in_valley(p) || p == to || p == from) //position is inside valley
+            .filter(move |&p| self.pos_is_blizzard_free(p, time + 1)) //no blizzard there
+    }
 
-fn parse(input: &str) -> Valley {
-    let mut blizzards = Vec::new();
-    let lines = input.lines().collect_vec();
-    let valley_size = (lines[0].len() as i32 - 2, lines.len() as i32 - 2);
-
-    for y in 0..lines.len() {
-        let line = lines[y];
-        for x in 0..line.len(){
-            let c = line.chars().nth(x).unwrap();
-            let (x, y) = (x as i32 - 1, y as i32 - 1);
-            let blizzard = match c {
-                '>' => Some(Blizzard::new((x,y), valley_size, Direction::Right)),
-                '<' => Some(Blizzard::new((x,y), valley_size, Direction::Left)),
-                '^' => Some(Blizzard::new((x,y), valley_size, Direction::Up)),
-                'v' => Some(Blizzard::new((x,y), valley_size, Direction::Down)),
-                _ => None,
-            };
-            if let Some(blizzard) = blizzard{
-                blizzards.push(blizzard);
-            }
-        }
+    //Whether `to` can be reached from `from` at all. The expedition can wait at `from` for as long as it takes,
+    //so only one cycle of the blizzards has to be searched, from any time
+    fn can_cross(&self, from: Point2, to: Point2) -> bool {
+        let period = lcm(self.size.x, self.size.y);
+        let states = |&(pos, time): &(Point2, i32)| self.moves(pos, time, from, to).map(move |p| ((p, (time + 1) % period), 1));
+        search::bfs(&states, [(from, 0)], |&(pos, _)| pos == to).is_some()
     }
 
-    let column_blizzards = (0..valley_size.0).map(|x| blizzards.iter().enumerate()
-        .filter(|(_,b)| b.dir == Direction::Up || b.dir == Direction::Down)
-        .filter(|(_,b)|  b.start_pos.0 == x)
-        .map(|(i,_)| i).collect()).collect_vec();
+    fn pos_is_in_valley(&self, pos: Point2) -> bool {
+        self.blizzards.contains(pos.x, pos.y)
+    }
 
-    let row_blizzards = (0..valley_size.1).map(|y| blizzards.iter().enumerate()
-        .filter(|(_,b)| b.dir == Direction::Left || b.dir == Direction::Right)
-        .filter(|(_,b)|  b.start_pos.1 == y)
-        .map(|(i,_)| i).collect()).collect_vec();
+    fn pos_is_blizzard_free(&self, pos: Point2, time: i32) -> bool{
+        match self.pos_is_in_valley(pos){
+            true => self.blizzards_at(pos, time).next().is_none(),
+            false => true
+        }
+    }
 
-    Valley {
-        blizzards,
-        column_blizzards,
-        row_blizzards,
-        size: valley_size
+    //Directions of the blizzards inside the valley at pos
+    fn blizzards_at(&self, pos: Point2, time: i32) -> impl Iterator<Item = Dir4> + '_ {
+        //Blizzards wrap around, so look back from pos to where a blizzard heading there would have started
+        Dir4::ALL.into_iter().filter(move |&dir| {
+            let start = pos - dir.offset() * time;
+            *self.blizzards.get_wrapping(start.x, start.y) == Some(dir)
+        })
+    }
+
+    //The valley and its walls with the expedition (E) in it, showing the number of blizzards where they overlap
+    fn frame(&self, expedition: Point2, time: i32) -> Frame {
+        let (entrance, exit) = self.entrance_and_exit();
+        let picture = (-1..=self.size.y).map(|y| (-1..=self.size.x).map(|x| {
+            let pos = Point2::new(x, y);
+            let blizzards = match self.pos_is_in_valley(pos) {
+                true => self.blizzards_at(pos, time).collect(),
+                false => vec![],
+            };
+            match blizzards[..] {
+                _ if pos == expedition => 'E',
+                _ if !self.pos_is_in_valley(pos) && pos != entrance && pos != exit => '#',
+                [] => '.',
+                [Dir4::Up] => '^',
+                [Dir4::Right] => '>',
+                [Dir4::Down] => 'v',
+                [Dir4::Left] => '<',
+                _ => char::from_digit(blizzards.len() as u32, 10).unwrap(),
+            }
+        }).collect::<String>()).collect::<Vec<String>>().join("\n");
+        Frame::new(format!("minute {}", time), picture)
     }
 }
 
-fn manhattan(p1: (i32,i32), p2: (i32,i32)) -> i32 {
-    (p1.0 - p2.0).abs() + (p1.1 - p2.1).abs()
-}
\ No newline at end of file
+fn parse(input: &str) -> Result<Valley, ParseError> {
+    let map = Grid::parse(input, |c| matches!(c, '#' | '.' | '>' | '<' | '^' | 'v').then_some(c), "a wall, open ground or a blizzard")?;
+    if map.width() < 3 || map.height() < 3 {
+        return Err(ParseError::at(input, input, "a valley surrounded by walls"));
+    }
+    let blizzards = Grid::from_fn(map.width() - 2, map.height() - 2, |x, y| match map[(x + 1, y + 1)] {
+        '>' => Some(Dir4::Right),
+        '<' => Some(Dir4::Left),
+        '^' => Some(Dir4::Up),
+        'v' => Some(Dir4::Down),
+        _ => None,
+    });
+    let size = Point2::new(blizzards.width() as i32, blizzards.height() as i32);
+
+    Ok(Valley {
+        blizzards,
+        size
+    })
+}
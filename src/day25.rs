@@ -1,26 +1,47 @@
-use itertools::Itertools;
+use crate::{ParseError, Solution};
+use crate::generate::{Generate, Rng};
 
-const INPUT: &str = include_str!("../input/2022/day25.txt");
+pub struct Day25;
 
-fn main() {
-    let lines = INPUT.split('\n').collect::<Vec<&str>>();
-    let converted = lines.iter().map(|l| convert_snafu(l)).collect_vec();
-    let decimal = converted.iter().sum::<i64>();
-    println!("Part 1: {}", convert_to_snafu(decimal));
-}
+impl Solution for Day25 {
+    type Input = Vec<i64>;
+    type Part1 = String;
+    type Part2 = &'static str;
 
-fn convert_snafu(s: &str) -> i64 {
-    let n_digits = s.trim().len();
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.lines().map(|l| match l.char_indices().find(|(_, c)| !matches!(c, '=' | '-' | '0'..='2')) {
+            Some((i, c)) => Err(ParseError::at(input, &l[i..i + c.len_utf8()], "a SNAFU digit (=, -, 0, 1 or 2)")),
+            None => convert_snafu(l).ok_or_else(|| ParseError::at(input, l, "a SNAFU number that fits in 64 bits")),
+        }).collect()
+    }
 
-    let mut result = 0;
-    for (c, place) in (s.chars().rev()).zip(0..n_digits) {
-        match c {
-            '-' => result -= 5_i64.pow(place as u32),
-            '=' => result -= 2 * 5_i64.pow(place as u32),
-            _ => result += (c as i64 - 0x30) * 5_i64.pow(place as u32)
-        }
+    fn part1(numbers: &Self::Input) -> String {
+        let decimal = numbers.iter().sum::<i64>();
+        convert_to_snafu(decimal)
+    }
+
+    fn part2(_: &Self::Input) -> &'static str {
+        //There is no second puzzle on the last day
+        "Merry Christmas!"
     }
-    result
+}
+
+impl Generate for Day25 {
+    fn generate(rng: &mut Rng, size: usize) -> String {
+        (0..size.max(1)).map(|_| convert_to_snafu(rng.range(1..=1_000_000_000_000)) + "\n").collect()
+    }
+}
+
+//None if the number does not fit in an i64
+fn convert_snafu(s: &str) -> Option<i64> {
+    s.trim().chars().try_fold(0_i64, |result, c| {
+        let digit = match c {
+            '-' => -1,
+            '=' => -2,
+            _ => c as i64 - 0x30,
+        };
+        result.checked_mul(5)?.checked_add(digit)
+    })
 }
 
 fn convert_to_snafu(n: i64) -> String {
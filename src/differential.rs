@@ -0,0 +1,151 @@
+use std::fmt;
+use std::ops::Range;
+
+use itertools::Itertools;
+
+use crate::generate::{no_panic, Rng};
+use crate::input::{self, InputSource};
+use crate::solution::solve;
+use crate::{day05, day05_2, day06, day07, registry, ParseError, Solution};
+
+//Computes something from the text of an input, in a form that can be compared between implementations
+pub type ImplFn = fn(&str) -> Result<String, ParseError>;
+
+//An optimised implementation and the straightforward one it has to agree with on every input
+pub struct Pair {
+    pub year: u16,
+    pub day: u8,
+    pub name: &'static str,
+    pub reference: ImplFn,
+    pub optimised: ImplFn,
+}
+
+//Registering an optimised solver is a matter of adding it here next to its reference implementation
+pub const PAIRS: &[Pair] = &[
+    Pair { year: 2022, day: 5, name: "reverse position tracking", reference: answers::<day05::Day05>, optimised: answers::<day05_2::Day05Reverse> },
+    Pair { year: 2022, day: 6, name: "efficient marker search", reference: markers_naive, optimised: markers_efficient },
+    Pair { year: 2022, day: 6, name: "multithreaded marker search", reference: markers_naive, optimised: markers_multithreaded },
+    Pair { year: 2022, day: 7, name: "non recursive parsing", reference: dir_sizes_recursive, optimised: dir_sizes_non_recursive },
+];
+
+//First input on which the two implementations of a pair disagree, or either of them panics
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub year: u16,
+    pub day: u8,
+    pub name: &'static str,
+    pub input: String,
+    pub reference: String,
+    pub optimised: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} day {:02} ({}): expected {} but got {} on input {:?}",
+            self.year, self.day, self.name, self.reference, self.optimised, self.input)
+    }
+}
+
+//Runs both implementations of a pair on every input, stopping at the first one they disagree on or panic on
+pub fn compare(pair: &Pair, inputs: impl IntoIterator<Item = String>) -> Result<usize, Divergence> {
+    let mut n_inputs = 0;
+    for input in inputs {
+        match (outcome(pair.reference, &input), outcome(pair.optimised, &input)) {
+            (Ok(reference), Ok(optimised)) if reference == optimised => n_inputs += 1,
+            (reference, optimised) => {
+                let (reference, optimised) = (reference.unwrap_or_else(|panic| panic), optimised.unwrap_or_else(|panic| panic));
+                return Err(Divergence { year: pair.year, day: pair.day, name: pair.name, input, reference, optimised });
+            }
+        }
+    }
+    Ok(n_inputs)
+}
+
+//The real input of the day of a pair (if there is one) followed by `n_cases` generated ones of growing size
+pub fn inputs(pair: &Pair, rng: &mut Rng, n_cases: usize) -> Vec<String> {
+    let generate = registry::get(pair.year, pair.day).expect("pair of a registered day").generate;
+    input::load(pair.year, pair.day, &InputSource::Default).into_iter()
+        .chain((0..n_cases).map(|size| generate(rng, size + 1)))
+        .collect()
+}
+
+//Answer or parse error, so that rejecting an input the same way also counts as agreeing, or else the panic message
+fn outcome(f: ImplFn, input: &str) -> Result<String, String> {
+    match no_panic(|| f(input)) {
+        Ok(Ok(output)) => Ok(output),
+        Ok(Err(e)) => Ok(format!("error: {}", e)),
+        Err(message) => Err(format!("panic: {}", message)),
+    }
+}
+
+fn answers<S: Solution>(input: &str) -> Result<String, ParseError> {
+    solve::<S>(input, None).map(|(answers, _)| format!("{:?} {:?}", answers.part_1, answers.part_2))
+}
+
+//Every marker length up to the one of part 2
+const MARKER_LENGTHS: Range<usize> = 1..15;
+
+fn markers_naive(input: &str) -> Result<String, ParseError> {
+    let chars = day06::Day06::parse(input)?;
+    Ok(format!("{:?}", MARKER_LENGTHS.map(|n| day06::find_marker_naive(&chars, n)).collect_vec()))
+}
+
+fn markers_efficient(input: &str) -> Result<String, ParseError> {
+    let chars = day06::Day06::parse(input)?;
+    Ok(format!("{:?}", MARKER_LENGTHS.map(|n| day06::find_marker_efficient(&chars, 0, chars.len(), n)).collect_vec()))
+}
+
+fn markers_multithreaded(input: &str) -> Result<String, ParseError> {
+    let chars = day06::Day06::parse(input)?;
+    Ok(format!("{:?}", day06::find_range_markers_mt(&chars, MARKER_LENGTHS)))
+}
+
+//Sizes of all directories, smallest first
+fn dir_sizes_recursive(input: &str) -> Result<String, ParseError> {
+    let lines = input.lines().collect_vec();
+    let mut dirs = vec![];
+    //The recursive parser starts inside the root directory, after "$ cd /"
+    day07::parse_dir(input, &mut lines.get(1..).unwrap_or_default().iter(), &mut dirs)?;
+    dirs.sort_unstable();
+    Ok(format!("{:?}", dirs))
+}
+
+fn dir_sizes_non_recursive(input: &str) -> Result<String, ParseError> {
+    let lines = input.lines().collect_vec();
+    let mut dirs = vec![];
+    day07::parse_dir_non_recursive(input, &mut lines.iter(), &mut dirs)?;
+    dirs.sort_unstable();
+    Ok(format!("{:?}", dirs))
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::{compare, inputs, Pair, PAIRS};
+    use crate::generate::Rng;
+
+    #[test]
+    fn optimised_implementations_agree_with_their_references() {
+        let divergences = PAIRS.iter()
+            .filter_map(|pair| compare(pair, inputs(pair, &mut Rng::new(pair.day as u64), 30)).err())
+            .collect_vec();
+        assert!(divergences.is_empty(), "{}", divergences.iter().join("\n"));
+    }
+
+    #[test]
+    fn compare_reports_first_diverging_input() {
+        let pair = Pair { year: 2022, day: 1, name: "broken", reference: |s| Ok(s.len().to_string()), optimised: |s| Ok(s.len().min(2).to_string()) };
+        let divergence = compare(&pair, ["a", "ab", "abc", "abcd"].map(String::from)).unwrap_err();
+        assert_eq!((divergence.input.as_str(), divergence.reference.as_str(), divergence.optimised.as_str()), ("abc", "3", "2"));
+        assert_eq!(compare(&pair, ["a", "ab"].map(String::from)).unwrap(), 2);
+    }
+
+    #[test]
+    fn panicking_the_same_way_is_no_agreement() {
+        let pair = Pair { year: 2022, day: 1, name: "panics", reference: |s| Ok(s[..2].to_string()), optimised: |s| Ok(s[..2].to_string()) };
+        let divergence = compare(&pair, ["ab", "a"].map(String::from)).unwrap_err();
+        assert_eq!(divergence.input, "a");
+        assert!(divergence.reference.starts_with("panic: ") && divergence.reference == divergence.optimised);
+    }
+}
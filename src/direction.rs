@@ -0,0 +1,120 @@
+use crate::Point2;
+
+//Quarter turn to either side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+//Horizontal and vertical directions on a map where y grows downwards, in clockwise order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+//Horizontal, vertical and diagonal compass directions on a map where y grows downwards, in clockwise order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dir8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Dir4 {
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    //Step of a single tile in this direction
+    pub fn offset(self) -> Point2 {
+        match self {
+            Dir4::Up => Point2::new(0, -1),
+            Dir4::Right => Point2::new(1, 0),
+            Dir4::Down => Point2::new(0, 1),
+            Dir4::Left => Point2::new(-1, 0),
+        }
+    }
+
+    //Direction after `steps` quarter turns clockwise (counterclockwise if negative)
+    pub fn rotate(self, steps: i32) -> Self {
+        Self::ALL[(self as i32 + steps).rem_euclid(4) as usize]
+    }
+
+    pub fn turn(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Left => self.rotate(-1),
+            Turn::Right => self.rotate(1),
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        self.rotate(2)
+    }
+}
+
+impl Dir8 {
+    pub const ALL: [Dir8; 8] = [Dir8::N, Dir8::NE, Dir8::E, Dir8::SE, Dir8::S, Dir8::SW, Dir8::W, Dir8::NW];
+
+    pub fn offset(self) -> Point2 {
+        match self {
+            Dir8::N => Point2::new(0, -1),
+            Dir8::NE => Point2::new(1, -1),
+            Dir8::E => Point2::new(1, 0),
+            Dir8::SE => Point2::new(1, 1),
+            Dir8::S => Point2::new(0, 1),
+            Dir8::SW => Point2::new(-1, 1),
+            Dir8::W => Point2::new(-1, 0),
+            Dir8::NW => Point2::new(-1, -1),
+        }
+    }
+
+    //Direction after `steps` eighth turns clockwise (counterclockwise if negative)
+    pub fn rotate(self, steps: i32) -> Self {
+        Self::ALL[(self as i32 + steps).rem_euclid(8) as usize]
+    }
+
+    pub fn turn(self, turn: Turn) -> Self {
+        match turn {
+            Turn::Left => self.rotate(-2),
+            Turn::Right => self.rotate(2),
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        self.rotate(4)
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        Dir8::ALL[2 * dir as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dir4, Dir8, Turn};
+    use crate::Point2;
+
+    #[test]
+    fn directions_turn_and_rotate() {
+        assert_eq!(Dir4::Up.turn(Turn::Right), Dir4::Right);
+        assert_eq!(Dir4::Up.turn(Turn::Left), Dir4::Left);
+        assert_eq!(Dir4::Left.rotate(-5), Dir4::Down);
+        assert_eq!(Dir4::Right.reverse(), Dir4::Left);
+        assert!(Dir4::ALL.iter().all(|d| d.turn(Turn::Right).offset() == d.offset().rotate_cw()));
+
+        assert_eq!(Dir8::NW.rotate(1), Dir8::N);
+        assert_eq!(Dir8::NE.turn(Turn::Right), Dir8::SE);
+        assert_eq!(Dir8::SW.reverse(), Dir8::NE);
+        assert!(Dir4::ALL.iter().all(|&d| Dir8::from(d).offset() == d.offset()));
+        assert_eq!(Dir8::ALL.iter().map(|d| d.offset()).fold(Point2::ORIGIN, |a, b| a + b), Point2::ORIGIN);
+    }
+}
@@ -0,0 +1,165 @@
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{env, fs, io};
+
+use crate::input;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+//Least time between two requests to the puzzle server, even across runs
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+//Base URL of the puzzle server, overridable with AOC_BASE_URL
+pub fn base_url() -> String {
+    env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string())
+}
+
+//Session cookie of a logged in user, from AOC_SESSION
+pub fn session() -> Option<String> {
+    env::var("AOC_SESSION").ok().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+//Downloads real inputs into an input tree, at the paths `input::load` reads them from
+pub struct Fetcher {
+    pub base_url: String,
+    pub session: Option<String>,
+    pub root: PathBuf,
+    pub min_interval: Duration,
+}
+
+impl Fetcher {
+    //Fetches from `base_url()` with `session()` into `input::input_dir()`
+    pub fn from_env() -> Self {
+        Fetcher { base_url: base_url(), session: session(), root: input::input_dir(), min_interval: DEFAULT_MIN_INTERVAL }
+    }
+
+    //Downloads the input of a day, unless an earlier download is still there
+    pub fn fetch(&self, year: u16, day: u8) -> io::Result<Fetched> {
+        let path = input::input_path_in(&self.root, year, day, None);
+        if path.exists() {
+            return Ok(Fetched::Cached(path));
+        }
+        let session = self.session.as_deref()
+            .ok_or_else(|| io::Error::other("set AOC_SESSION to the session cookie of a logged in user to download inputs"))?;
+        self.throttle()?;
+
+        let url = format!("{}/{}/day/{}/input", self.base_url.trim_end_matches('/'), year, day);
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("aoc2022/", env!("CARGO_PKG_VERSION"), " input fetcher"))
+            .build();
+        let input = match agent.get(&url).set("Cookie", &format!("session={}", session)).call() {
+            Ok(response) => response.into_string()?,
+            Err(ureq::Error::Status(404, _)) => return Err(io::Error::new(io::ErrorKind::NotFound, format!("{}: day {} of {} is not available (yet)", url, day, year))),
+            Err(ureq::Error::Status(429, response)) => {
+                let retry = response.header("Retry-After").map(|s| format!(", retry after {}s", s)).unwrap_or_default();
+                return Err(io::Error::other(format!("{}: too many requests{}", url, retry)));
+            }
+            Err(ureq::Error::Status(code, _)) => return Err(io::Error::other(format!("{}: the server answered {}, is the session still valid?", url, code))),
+            Err(e) => return Err(io::Error::other(format!("{}: {}", url, e))),
+        };
+        if input.trim().is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("{}: the server sent an empty input", url)));
+        }
+
+        //Write next to the input and move it in place, so an interrupted download is never mistaken for a cached input
+        fs::create_dir_all(path.parent().unwrap())?;
+        let partial = path.with_extension("txt.part");
+        fs::write(&partial, input)?;
+        fs::rename(&partial, &path)?;
+        Ok(Fetched::Downloaded(path))
+    }
+
+    //Waits until `min_interval` has passed since the last request, and records the time of the next one
+    fn throttle(&self) -> io::Result<()> {
+        let stamp = self.root.join(".last_fetch");
+        let last = fs::read_to_string(&stamp).ok().and_then(|s| s.trim().parse::<u64>().ok()).map(Duration::from_millis);
+        if let Some(wait) = last.and_then(|last| (last + self.min_interval).checked_sub(now())) {
+            log::info!("waiting {:.1?} before the next request", wait);
+            thread::sleep(wait);
+        }
+        fs::create_dir_all(&self.root)?;
+        fs::write(&stamp, now().as_millis().to_string())
+    }
+}
+
+fn now() -> Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+    use std::{env, fs, process, thread};
+
+    use super::{Fetched, Fetcher};
+    use crate::input;
+
+    //Serves `body` for every request until `n_requests` were answered, returning the base URL and the requests
+    fn stub_server(status: &'static str, body: &'static str, n_requests: usize) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            listener.incoming().take(n_requests).map(|stream| {
+                let mut stream = stream.unwrap();
+                let request = BufReader::new(&stream).lines().map(Result::unwrap).take_while(|line| !line.is_empty()).collect::<Vec<String>>();
+                write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body).unwrap();
+                request.join("\n")
+            }).collect()
+        });
+        (url, server)
+    }
+
+    fn test_root(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("aoc-fetch-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&root);
+        root
+    }
+
+    #[test]
+    fn inputs_are_downloaded_once_and_throttled() {
+        let (url, server) = stub_server("200 OK", "1\n2\n3\n", 2);
+        let root = test_root("cache");
+        let fetcher = Fetcher { base_url: url + "/", session: Some("secret".to_string()), root: root.clone(), min_interval: Duration::from_millis(300) };
+
+        let path = input::input_path_in(&root, 2022, 1, None);
+        assert_eq!(fetcher.fetch(2022, 1).unwrap(), Fetched::Downloaded(path.clone()));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+        assert!(path.ends_with("2022/day01.txt"));
+        assert_eq!(fetcher.fetch(2022, 1).unwrap(), Fetched::Cached(path));
+
+        let start = Instant::now();
+        assert!(matches!(fetcher.fetch(2022, 2).unwrap(), Fetched::Downloaded(_)));
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].starts_with("GET /2022/day/1/input HTTP/1.1"));
+        assert!(requests[0].lines().any(|line| line.eq_ignore_ascii_case("cookie: session=secret")));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn failed_downloads_are_not_cached() {
+        let (url, server) = stub_server("400 Bad Request", "Please log in", 1);
+        let root = test_root("failed");
+        let fetcher = Fetcher { base_url: url, session: Some("expired".to_string()), root: root.clone(), min_interval: Duration::ZERO };
+
+        assert!(fetcher.fetch(2022, 3).unwrap_err().to_string().contains("400"));
+        assert!(!input::input_path_in(&root, 2022, 3, None).exists());
+        server.join().unwrap();
+
+        let fetcher = Fetcher { session: None, ..fetcher };
+        assert!(fetcher.fetch(2022, 3).unwrap_err().to_string().contains("AOC_SESSION"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,191 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::panic::{self, AssertUnwindSafe};
+
+use itertools::Itertools;
+use num::PrimInt;
+
+use crate::registry::Day;
+use crate::Solution;
+
+//Small deterministic random number generator (SplitMix64), so a seed always makes the same inputs
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    //Uniformly distributed value in a non-empty range
+    pub fn range<T: PrimInt>(&mut self, range: RangeInclusive<T>) -> T {
+        let (start, end) = (range.start().to_i128().unwrap(), range.end().to_i128().unwrap());
+        assert!(start <= end, "empty range");
+        let span = (end - start + 1) as u128;
+        T::from(start + (self.next_u64() as u128 % span) as i128).unwrap()
+    }
+
+    //True with probability `p`
+    pub fn chance(&mut self, p: f64) -> bool {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64 > 1.0 - p
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..=items.len() - 1)]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..=i));
+        }
+    }
+}
+
+//A solution that can make up random, valid inputs for itself
+pub trait Generate: Solution {
+    //An input with about `size` of the things the puzzle is made of (elves, sensors, numbers, rows of a map...)
+    fn generate(rng: &mut Rng, size: usize) -> String;
+}
+
+//Input that made a parser or solver panic (or a solver reject its own generated input)
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub year: u16,
+    pub day: u8,
+    pub input: String,
+    pub message: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} day {:02}: {} on input {:?}", self.year, self.day, self.message, self.input)
+    }
+}
+
+//Splicing these into an input trips up parsers that trust positions, lengths or number sizes
+const TRICKY: &[&str] = &[
+    " ", "\n", "\n\n", ",", ":", "-", "=", "-1", "0", "é", "→", "[[", "]", "2147483647", "-2147483648", "4294967295",
+    "18446744073709551615", "99999999999999999999", "old", "root", "humn", "Each", " -> ", "$ cd ..",
+];
+
+//Runs the parser of a day on `n_cases` random texts and mutated valid inputs, and its solver on the ones that parse,
+//failing on the first one either panics on
+pub fn fuzz_parser(day: &Day, rng: &mut Rng, n_cases: usize) -> Result<(), Failure> {
+    for case in 0..n_cases {
+        let size = rng.range(1..=6);
+        let valid = (day.generate)(rng, size);
+        //Characters of a valid input make it likely for random text to get past the first checks
+        let alphabet = valid.chars().chain(" \n,-:=0123456789é".chars()).unique().collect_vec();
+        let (input, solve) = match case % 4 {
+            0 => (random_text(rng, &alphabet), false),
+            1 => (mutate(rng, &valid, &alphabet, true), false),
+            //An input that did not grow stays about as hard as the generated one, so it can be solved
+            _ => (mutate(rng, &valid, &alphabet, false), true),
+        };
+        let solved = no_panic(|| if (day.parse)(&input).is_ok() && solve { let _ = (day.solve)(&input, None); });
+        if let Err(message) = solved {
+            return Err(Failure { year: day.year, day: day.day, input, message });
+        }
+    }
+    Ok(())
+}
+
+//Solves `n_cases` generated inputs of about `size` with a day's solver, failing on the first one it panics on or rejects
+pub fn check_solver(day: &Day, rng: &mut Rng, n_cases: usize, size: usize) -> Result<(), Failure> {
+    for _ in 0..n_cases {
+        let input = (day.generate)(rng, size);
+        let message = match no_panic(|| (day.solve)(&input, None).map(|_| ())) {
+            Ok(Ok(())) => continue,
+            Ok(Err(e)) => format!("rejected its generated input: {}", e),
+            Err(message) => message,
+        };
+        return Err(Failure { year: day.year, day: day.day, input, message });
+    }
+    Ok(())
+}
+
+fn random_text(rng: &mut Rng, alphabet: &[char]) -> String {
+    (0..rng.range(0..=100)).map(|_| match rng.chance(0.1) {
+        true => rng.pick(TRICKY).to_string(),
+        false => rng.pick(alphabet).to_string(),
+    }).collect()
+}
+
+//A few random edits to a valid input, only deleting or replacing characters unless it may `grow`
+fn mutate(rng: &mut Rng, input: &str, alphabet: &[char], grow: bool) -> String {
+    let mut chars = input.chars().collect_vec();
+    for _ in 0..rng.range(1..=4) {
+        let at = rng.range(0..=chars.len());
+        let end = rng.range(at..=chars.len().min(at + 20));
+        let edit = match grow {
+            true => rng.range(0..=5),
+            false => *rng.pick(&[0, 4, 5]),
+        };
+        match edit {
+            0 => { chars.drain(at..end); }
+            1 => chars.insert(at, *rng.pick(alphabet)),
+            2 => { chars.splice(at..at, rng.pick(TRICKY).chars()); }
+            3 => {
+                let copy = chars[at..end].to_vec();
+                chars.splice(at..at, copy);
+            }
+            4 => chars.truncate(at),
+            _ => if at < chars.len() {
+                chars[at] = *rng.pick(alphabet);
+            },
+        }
+    }
+    chars.into_iter().collect()
+}
+
+//Runs `f`, catching a panic as its message
+pub(crate) fn no_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload.downcast_ref::<&str>().map_or("panicked", |s| s).to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::{check_solver, fuzz_parser, Rng};
+    use crate::registry;
+
+    #[test]
+    fn nothing_panics_on_arbitrary_text() {
+        let failures = registry::DAYS.iter()
+            .filter_map(|day| fuzz_parser(day, &mut Rng::new(day.day as u64), 400).err())
+            .collect_vec();
+        assert!(failures.is_empty(), "{}", failures.iter().join("\n"));
+    }
+
+    #[test]
+    fn inputs_without_answers_are_errors() {
+        for (n, input) in [(1, "\n"), (6, "abcdefg\n"), (17, "\n"), (23, "....\n....\n")] {
+            let day = registry::DAYS.iter().find(|day| day.day == n).unwrap();
+            assert!((day.solve)(input, None).is_err(), "day {} solved {:?}", n, input);
+        }
+    }
+
+    #[test]
+    fn solvers_never_panic_on_generated_inputs() {
+        let failures = registry::DAYS.iter()
+            .filter_map(|day| {
+                //From the smallest inputs the generators make up; every blueprint of day 19 is a search of seconds
+                let sizes: &[usize] = if day.day == 19 { &[1, 2] } else { &[1, 3, 8] };
+                sizes.iter().find_map(|&size| check_solver(day, &mut Rng::new(day.day as u64 * 100 + size as u64), 4, size).err())
+            })
+            .collect_vec();
+        assert!(failures.is_empty(), "{}", failures.iter().join("\n"));
+    }
+}
@@ -0,0 +1,198 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{ParseError, Point2};
+
+//Rectangular 2D map stored row by row and addressed as (x, y), where x is the column and y the row
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let cells = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).map(|(x, y)| f(x, y)).collect();
+        Grid { width, height, cells }
+    }
+
+    //Parses a character map with one row per non-empty line, turning every character into a cell with `cell`
+    pub fn parse(s: &str, mut cell: impl FnMut(char) -> Option<T>, expected: &str) -> Result<Self, ParseError> {
+        let mut grid = Grid { width: 0, height: 0, cells: vec![] };
+        for line in s.lines().filter(|line| !line.is_empty()) {
+            let row = line.char_indices()
+                .map(|(i, c)| cell(c).ok_or_else(|| ParseError::at(s, &line[i..i + c.len_utf8()], expected)))
+                .collect::<Result<Vec<T>, ParseError>>()?;
+            if grid.height > 0 && row.len() != grid.width {
+                return Err(ParseError::at(s, line, format!("a row of {} cells", grid.width)));
+            }
+            grid.push_row(row);
+        }
+        match grid.height {
+            0 => Err(ParseError::end(s, expected)),
+            _ => Ok(grid),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> Option<&T> {
+        self.contains(x, y).then(|| &self.cells[y as usize * self.width + x as usize])
+    }
+
+    pub fn get_mut(&mut self, x: i32, y: i32) -> Option<&mut T> {
+        match self.contains(x, y) {
+            true => Some(&mut self.cells[y as usize * self.width + x as usize]),
+            false => None,
+        }
+    }
+
+    //Cell at (x, y) on a map that repeats itself in every direction
+    pub fn get_wrapping(&self, x: i32, y: i32) -> &T {
+        let (x, y) = (x.rem_euclid(self.width as i32), y.rem_euclid(self.height as i32));
+        &self.cells[y as usize * self.width + x as usize]
+    }
+
+    //Horizontally and vertically adjacent positions inside the grid
+    pub fn neighbors4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors([(0, -1), (1, 0), (0, 1), (-1, 0)], x, y)
+    }
+
+    //Horizontally, vertically and diagonally adjacent positions inside the grid
+    pub fn neighbors8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors([(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)], x, y)
+    }
+
+    fn neighbors<const N: usize>(&self, offsets: [(i32, i32); N], x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        offsets.into_iter()
+            .map(move |(dx, dy)| (x as i32 + dx, y as i32 + dy))
+            .filter(|&(x, y)| self.contains(x, y))
+            .map(|(x, y)| (x as usize, y as usize))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} is outside of a {}x{} grid", x, self.width, self.height);
+        self.cells[x..].iter().step_by(self.width)
+    }
+
+    //Every cell, row by row
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    //Every position, in the same order as `iter`
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        self.cells.iter().position(predicate).map(|i| (i % self.width, i / self.width))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    //Adds a row below the others; the first row sets the width
+    pub fn push_row(&mut self, row: impl IntoIterator<Item = T>) {
+        let len = self.cells.len();
+        self.cells.extend(row);
+        if self.height == 0 {
+            self.width = self.cells.len();
+        }
+        assert_eq!(self.cells.len() - len, self.width, "row does not match the width of the grid");
+        self.height += 1;
+    }
+
+    //Draws every cell as a character, one line per row
+    pub fn render(&self, mut f: impl FnMut(&T) -> char) -> String {
+        self.rows().map(|row| row.iter().map(&mut f).collect::<String>()).collect::<Vec<String>>().join("\n")
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(x < self.width && y < self.height, "({}, {}) is outside of a {}x{} grid", x, y, self.width, self.height);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(x < self.width && y < self.height, "({}, {}) is outside of a {}x{} grid", x, y, self.width, self.height);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T> Index<Point2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point2) -> &T {
+        self.get(p.x, p.y).unwrap_or_else(|| panic!("{:?} is outside of a {}x{} grid", p, self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Point2> for Grid<T> {
+    fn index_mut(&mut self, p: Point2) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(p.x, p.y).unwrap_or_else(|| panic!("{:?} is outside of a {}x{} grid", p, width, height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    #[test]
+    fn grid_parses_and_addresses_cells() {
+        let grid = Grid::parse("#..\n.#.\n", |c| match c { '#' => Some(true), '.' => Some(false), _ => None }, "# or .").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert!(grid[(1, 1)] && !grid[(2, 1)]);
+        assert_eq!(grid.get(-1, 0), None);
+        assert_eq!(grid.get_wrapping(-3, 2), &true);
+        assert_eq!(grid.neighbors4(0, 0).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbors8(2, 1).count(), 3);
+        assert_eq!(grid.column(1).filter(|c| **c).count(), 1);
+        assert_eq!(grid.position(|c| *c), Some((0, 0)));
+        assert_eq!(grid.render(|c| if *c { '#' } else { '.' }), "#..\n.#.");
+
+        let err = Grid::parse("#..\n.#\n", |c| Some(c == '#'), "# or .").unwrap_err();
+        assert_eq!((err.line, err.column, err.expected.as_str()), (2, 1, "a row of 3 cells"));
+        let err = Grid::parse("#.x\n", |c| (c != 'x').then_some(c), "# or .").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "column 3 is outside of a 3x2 grid")]
+    fn columns_past_the_width_panic() {
+        let grid = Grid::parse("#..\n.#.\n", |c| Some(c == '#'), "# or .").unwrap();
+        let _ = grid.column(3).count();
+    }
+}
@@ -0,0 +1,60 @@
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use sha2::{Digest, Sha256};
+
+//Where the puzzle text of a day is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Default,
+    Variant(String),
+    Path(PathBuf),
+    Stdin,
+}
+
+//Root of the input tree, overridable with AOC_INPUT_DIR
+pub fn input_dir() -> PathBuf {
+    env::var_os("AOC_INPUT_DIR").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("input"))
+}
+
+//input/<year>/dayNN.txt, or dayNN_<variant>.txt for a named variant such as "test"
+pub fn input_path(year: u16, day: u8, variant: Option<&str>) -> PathBuf {
+    input_path_in(&input_dir(), year, day, variant)
+}
+
+//Same layout as `input_path`, under another root
+pub fn input_path_in(root: &Path, year: u16, day: u8, variant: Option<&str>) -> PathBuf {
+    let file = match variant {
+        Some(variant) => format!("day{:02}_{}.txt", day, variant),
+        None => format!("day{:02}.txt", day),
+    };
+    root.join(year.to_string()).join(file)
+}
+
+//input/<year>, holding the inputs of every day of the year
+pub fn year_dir(year: u16) -> PathBuf {
+    input_dir().join(year.to_string())
+}
+
+pub fn load(year: u16, day: u8, source: &InputSource) -> io::Result<String> {
+    match source {
+        InputSource::Default => read_file(&input_path(year, day, None)),
+        InputSource::Variant(variant) => read_file(&input_path(year, day, Some(variant))),
+        InputSource::Path(path) => read_file(path),
+        InputSource::Stdin => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+    }
+}
+
+//SHA-256 of the text of an input, telling whether results saved for an input belong to this one
+pub fn input_hash(input: &str) -> String {
+    format!("{:x}", Sha256::digest(input))
+}
+
+fn read_file(path: &Path) -> io::Result<String> {
+    fs::read_to_string(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+}
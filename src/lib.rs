@@ -1,17 +1,107 @@
+#![allow(clippy::needless_range_loop)]
+
 use std::str::FromStr;
 
-pub fn parse_to_vec<T : FromStr>(string : &str, sep: &str) -> Result<Vec<T>, ()> {
-    Ok(string
-        .split(sep)
-        .filter_map(|line| line.parse::<T>().ok())
+pub mod answers;
+pub mod bench;
+pub mod cache;
+pub mod checkpoint;
+pub mod cycle;
+pub mod differential;
+pub mod direction;
+pub mod fetch;
+pub mod generate;
+pub mod grid;
+pub mod input;
+pub mod logging;
+pub mod parallel;
+pub mod parse;
+pub mod point;
+pub mod range_set;
+pub mod registry;
+pub mod search;
+pub mod solution;
+pub mod visualize;
+
+pub use direction::{Dir4, Dir8, Turn};
+pub use grid::Grid;
+pub use parse::{sections, ParseError, Scanner};
+pub use point::{Point2, Point3};
+pub use range_set::RangeSet;
+pub use solution::Solution;
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day05_2;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day22_2;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+
+//Parses every non-empty record, failing on the first one that does not parse
+pub fn parse_to_vec<T : FromStr<Err = ParseError>>(string : &str, sep: &str) -> Result<Vec<T>, ParseError> {
+    parse_to_vec_custom(string, sep, |record| record.parse())
+}
+
+pub fn parse_to_vec_custom<T>(string: &str, sep: &str, f: fn(&str) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+    string.split(sep).filter(|record| !record.trim().is_empty())
+        .map(|record| f(record).map_err(|e| e.within(string, record)))
         .collect()
-    )
 }
 
-pub fn parse_to_vec_custom<T>(string: &str, sep: &str, f: fn(&str) -> Result<T, ()>) -> Result<Vec<T>, ()> {
-    Ok(string
-        .split(sep)
-        .filter_map(|line| f(line).ok())
+//Parses every part of the input between blank lines
+pub fn parse_sections<T: FromStr<Err = ParseError>>(string: &str) -> Result<Vec<T>, ParseError> {
+    sections(string).into_iter()
+        .map(|section| section.parse::<T>().map_err(|e| e.within(string, section)))
         .collect()
-    )
-}
\ No newline at end of file
+}
+
+//Parses every non-empty record, setting aside the ones that do not parse together with their error
+pub fn parse_to_vec_lenient<'a, T : FromStr<Err = ParseError>>(string : &'a str, sep: &str) -> (Vec<T>, Vec<(&'a str, ParseError)>) {
+    let (mut parsed, mut rejected) = (vec![], vec![]);
+    for record in string.split(sep).filter(|record| !record.trim().is_empty()) {
+        match record.parse::<T>() {
+            Ok(value) => parsed.push(value),
+            Err(e) => rejected.push((record, e.within(string, record))),
+        }
+    }
+    (parsed, rejected)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day09::Action;
+    use super::{parse_to_vec, parse_to_vec_lenient};
+
+    #[test]
+    fn carriage_returns_are_reported_instead_of_dropped() {
+        let input = "R 4\r\nU 4\nL 3\r\n\n";
+
+        let err = parse_to_vec::<Action>(input, "\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.found.as_str()), (1, 4, "\r"));
+
+        let (parsed, rejected) = parse_to_vec_lenient::<Action>(input, "\n");
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(rejected.iter().map(|(record, e)| (*record, e.line)).collect::<Vec<_>>(), [("R 4\r", 1), ("L 3\r", 3)]);
+    }
+}
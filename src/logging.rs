@@ -0,0 +1,127 @@
+use std::cell::RefCell;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+thread_local! {
+    //Names of the spans entered on this thread, outermost first
+    static SPANS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+//When logging started, for the timestamps of the messages
+static START: OnceLock<Instant> = OnceLock::new();
+
+//Writes messages to stderr, prefixed with the time since the start and the spans they were logged in
+struct Logger;
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            let elapsed = START.get().map_or_else(Default::default, Instant::elapsed);
+            eprintln!("{:>10.3?} {:<5} {}{}", elapsed, record.level(), span_prefix(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Logger = Logger;
+
+//Shows warnings, plus spans and their timings with -v (1), plus solver statistics and progress with -vv (2)
+//and everything with -vvv
+pub fn init(verbosity: u8) {
+    let level = match verbosity {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+    START.get_or_init(Instant::now);
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+//Names the work done on this thread until it is dropped: messages logged meanwhile are prefixed with its name, and the
+//time it took is logged at the end
+#[must_use = "a span ends as soon as it is dropped"]
+pub struct Span {
+    start: Instant,
+}
+
+pub fn span(name: impl Into<String>) -> Span {
+    SPANS.with(|spans| spans.borrow_mut().push(name.into()));
+    log::trace!("started");
+    Span { start: Instant::now() }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        log::info!("took {:.1?}", self.start.elapsed());
+        SPANS.with(|spans| spans.borrow_mut().pop());
+    }
+}
+
+//Spans entered on a thread, for the work it hands to other threads to be logged in them too
+#[derive(Debug, Clone, Default)]
+pub struct Context(Vec<String>);
+
+pub fn context() -> Context {
+    SPANS.with(|spans| Context(spans.borrow().clone()))
+}
+
+impl Context {
+    //Runs `f` in the spans of this context instead of those of the current thread. A pool thread may run work of
+    //unrelated spans while it waits, so they are swapped rather than added to
+    pub fn run<T>(&self, f: impl FnOnce() -> T) -> T {
+        struct Restore(Vec<String>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                SPANS.with(|spans| *spans.borrow_mut() = std::mem::take(&mut self.0));
+            }
+        }
+        let _restore = Restore(SPANS.with(|spans| spans.replace(self.0.clone())));
+        f()
+    }
+}
+
+//"day16 part2: " inside the spans "day16" and "part2"
+fn span_prefix() -> String {
+    SPANS.with(|spans| match spans.borrow().is_empty() {
+        true => String::new(),
+        false => format!("{}: ", spans.borrow().join(" ")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{context, span, span_prefix};
+
+    #[test]
+    fn spans_nest_per_thread() {
+        assert_eq!(span_prefix(), "");
+        let outer = span("day16");
+        {
+            let _inner = span("part2");
+            assert_eq!(span_prefix(), "day16 part2: ");
+            std::thread::spawn(|| assert_eq!(span_prefix(), "")).join().unwrap();
+            let context = context();
+            std::thread::spawn(move || {
+                let _other = span("day19");
+                context.run(|| {
+                    let _blueprint = span("blueprint1");
+                    assert_eq!(span_prefix(), "day16 part2 blueprint1: ");
+                });
+                assert_eq!(span_prefix(), "day19: ");
+            }).join().unwrap();
+        }
+        assert_eq!(span_prefix(), "day16: ");
+        drop(outer);
+        assert_eq!(span_prefix(), "");
+    }
+}
@@ -0,0 +1,733 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::process;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use itertools::Itertools;
+use rayon::prelude::*;
+use serde_json::{json, Map, Value};
+
+use aoc2022::answers::{self, Manifest};
+use aoc2022::bench::{growth_exponent, regression, Baseline, Config, Phase, Stats, Timings};
+use aoc2022::cache::AnswerCache;
+use aoc2022::checkpoint::{self, checkpoint_path, Checkpoint};
+use aoc2022::fetch::{Fetched, Fetcher};
+use aoc2022::generate::Rng;
+use aoc2022::input::{self, InputSource};
+use aoc2022::{logging, parallel};
+use aoc2022::registry::{self, Day};
+use aoc2022::solution::{Answers, Elapsed};
+use aoc2022::visualize::{Dump, Player, Recorder, Viewer};
+use aoc2022::ParseError;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Year of the puzzles [default: the latest year with solutions]
+    #[arg(long, global = true)]
+    year: Option<u16>,
+    /// How results are printed; progress and diagnostics always go to stderr
+    #[arg(long, value_enum, global = true, default_value_t = Format::Text)]
+    format: Format,
+    /// Log the time taken by every stage (-v), and also solver statistics and progress (-vv), to stderr
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+    /// Threads the parallel solvers may use; 1 solves everything sequentially [default: AOC_THREADS, or one per CPU]
+    #[arg(long, global = true, value_parser = clap::value_parser!(u32).range(1..))]
+    threads: Option<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Human-readable answers and tables
+    Text,
+    /// One JSON object per day, each on its own line
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve a single day, or all of them in parallel, checked against the known answers
+    Run {
+        /// Day to solve (1-25), or "all"
+        day: Selection,
+        /// Only solve this part
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Solve again even if the answers for the same input and solver version are in target/answer_cache
+        #[arg(long)]
+        no_cache: bool,
+        /// Pick up the searches of days 16 and 19 from the checkpoint an interrupted run left in target/checkpoints
+        #[arg(long)]
+        resume: bool,
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        visualize: VisualizeArgs,
+    },
+    /// Time parsing and both parts of a single day, or all of them
+    Bench {
+        /// Day to benchmark (1-25), or "all"
+        day: Selection,
+        /// Timed iterations of every phase
+        #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        iterations: u32,
+        /// Untimed iterations of every phase before the timed ones
+        #[arg(long, default_value_t = 2)]
+        warmup: u32,
+        /// Baseline file to flag regressions against
+        #[arg(long, default_value = "target/bench_baseline.json")]
+        baseline: PathBuf,
+        /// Save the medians of this run to the baseline file. The baseline only holds timings of the puzzle inputs
+        #[arg(long, conflicts_with_all = ["input", "example", "variant"])]
+        save_baseline: bool,
+        /// Slowdown of a median over the baseline, in percent, that is flagged as a regression
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+        /// Time generated inputs of these sizes instead of the puzzle input, to see how the phases scale
+        #[arg(long, value_delimiter = ',', value_name = "SIZES", conflicts_with_all = ["input", "example", "variant", "save_baseline"])]
+        sizes: Vec<usize>,
+        /// Seed of the inputs generated for --sizes
+        #[arg(long, default_value_t = 1, requires = "sizes")]
+        seed: u64,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Make up a random, valid input for a day, or for every day
+    Generate {
+        /// Day to generate an input for (1-25), or "all"
+        day: Selection,
+        /// About how many of the things the puzzle is made of: elves, sensors, numbers, rows of a map...
+        #[arg(long, default_value_t = 100)]
+        size: usize,
+        /// Seed of the generator; the same seed and size always make the same input
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+        /// Save the input as this variant (dayNN_<NAME>.txt), to be read with --variant, instead of printing it
+        #[arg(long, value_name = "NAME")]
+        variant: Option<String>,
+    },
+    /// Download the input of a day, or of every day, unless it was downloaded before
+    ///
+    /// Inputs are requested from AOC_BASE_URL (https://adventofcode.com by default) with the session cookie in AOC_SESSION,
+    /// at most once every few seconds, and saved where `run` reads them from.
+    Fetch {
+        /// Day to download (1-25), or "all"
+        day: Selection,
+    },
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// Read the puzzle input from this file ("-" for stdin)
+    #[arg(long, conflicts_with_all = ["example", "variant"])]
+    input: Option<PathBuf>,
+    /// Use the example input (dayNN_test.txt)
+    #[arg(long, conflicts_with = "variant")]
+    example: bool,
+    /// Use a named input variant (dayNN_<NAME>.txt)
+    #[arg(long, value_name = "NAME")]
+    variant: Option<String>,
+}
+
+impl InputArgs {
+    fn source(self) -> InputSource {
+        match (self.input, self.example, self.variant) {
+            (Some(path), _, _) if path.as_os_str() == "-" => InputSource::Stdin,
+            (Some(path), _, _) => InputSource::Path(path),
+            (None, true, _) => InputSource::Variant("test".to_string()),
+            (None, false, Some(variant)) => InputSource::Variant(variant),
+            (None, false, None) => InputSource::Default,
+        }
+    }
+}
+
+#[derive(Args)]
+struct VisualizeArgs {
+    /// Watch the simulation behind the answers step by step (days 14, 17, 23 and 24)
+    #[arg(long)]
+    visualize: bool,
+    /// Frames shown per second while watching
+    #[arg(long, default_value_t = 20.0, requires = "visualize", value_parser = frame_rate)]
+    fps: f64,
+    /// Only show every Nth step of the simulation
+    #[arg(long, value_name = "N", default_value_t = 1, requires = "visualize", value_parser = clap::value_parser!(u32).range(1..))]
+    every: u32,
+    /// Start paused; while watching, Enter plays or pauses, s and Enter steps and q and Enter stops watching
+    #[arg(long, requires = "visualize")]
+    paused: bool,
+    /// Write the frames to this file instead of playing them
+    #[arg(long, value_name = "FILE", requires = "visualize")]
+    dump: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy)]
+enum Selection {
+    All,
+    Day(u8),
+}
+
+impl FromStr for Selection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Selection::All),
+            _ => match s.parse::<u8>() {
+                Ok(day) if (1..=25).contains(&day) => Ok(Selection::Day(day)),
+                _ => Err(format!("{} is not a day (1-25) or \"all\"", s)),
+            }
+        }
+    }
+}
+
+//Frames per second: positive, and not so few that a frame would last longer than a Duration can hold
+fn frame_rate(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(fps) if fps > 0.0 && fps.is_finite() && Duration::try_from_secs_f64(1.0 / fps).is_ok() => Ok(fps),
+        _ => Err(format!("{} is not a usable number of frames per second", s)),
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let format = cli.format;
+    let year = cli.year.unwrap_or_else(registry::latest_year);
+    logging::init(cli.verbose);
+    //Searches interrupted with Ctrl-C leave what they completed in their checkpoints, to be resumed
+    if let Err(e) = ctrlc::set_handler(|| {
+        checkpoint::save_open();
+        process::exit(130);
+    }) {
+        log::warn!("could not handle interruptions: {}", e);
+    }
+    let threads_var = env::var(parallel::THREADS_VAR).ok();
+    if let Err(e) = parallel::requested_threads(cli.threads.map(|threads| threads as usize), threads_var.as_deref()).and_then(parallel::init) {
+        eprintln!("error: {}", e);
+        process::exit(2);
+    }
+    //Inputs can be fetched before any day of their year is solved
+    if !matches!(cli.command, Command::Fetch { .. }) && registry::days(year).next().is_none() {
+        eprintln!("error: no solutions for {}, only for {}", year, registry::years().iter().join(", "));
+        process::exit(2);
+    }
+
+    let failed = match cli.command {
+        Command::Run { day: Selection::All, visualize, .. } if visualize.visualize => {
+            eprintln!("error: --visualize can only be used with a single day");
+            process::exit(2);
+        }
+        Command::Run { day: Selection::All, resume: true, .. } => {
+            eprintln!("error: --resume can only be used with a single day");
+            process::exit(2);
+        }
+        Command::Run { day: Selection::All, part, no_cache, input, .. } => run_all(year, part, &input.source(), format, !no_cache),
+        Command::Run { day, part, no_cache, resume, input, visualize } => {
+            let source = input.source();
+            let days = selected_days(year, day, &source);
+            if visualize.visualize && (format == Format::Json || days[0].visualize.is_none()) {
+                eprintln!("error: --visualize is only available for days 14, 17, 23 and 24 in text format");
+                process::exit(2);
+            }
+            for_each_day(&days, &source, format, |day, input| {
+                if visualize.visualize {
+                    watch(day, part, input, &source, &visualize)?;
+                }
+                run(day, part, input, &source, format, resume, !no_cache)
+            })
+        }
+        Command::Bench { day, iterations, warmup, sizes, seed, .. } if !sizes.is_empty() => {
+            bench_sizes(&selected_days(year, day, &InputSource::Default), &sizes, seed, &Config { iterations, warmup }, format)
+        }
+        Command::Bench { day, iterations, warmup, baseline: baseline_path, save_baseline, threshold, input, .. } => {
+            let source = input.source();
+            let config = Config { iterations, warmup };
+            let mut baseline = Baseline::load(&baseline_path).unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                process::exit(2);
+            });
+            //Timings of other inputs are not comparable with the baseline of the puzzle inputs
+            let previous = match source {
+                InputSource::Default => baseline.clone(),
+                _ => Baseline::default(),
+            };
+            let mut n_regressions = 0;
+
+            let failed = for_each_day(&selected_days(year, day, &source), &source, format, |day, input| {
+                if format == Format::Text {
+                    println!("Day {:02}", day.day);
+                }
+                let timings = (day.bench)(input, &config)?;
+                let regressions = Phase::ALL.map(|phase| {
+                    let median = timings.get(phase).median;
+                    previous.get(day.year, day.day, phase).and_then(|before| regression(before, median, threshold / 100.0).map(|change| (before, change)))
+                });
+                n_regressions += regressions.iter().flatten().count();
+                match format {
+                    Format::Text => print_timings(&timings, &regressions),
+                    Format::Json => {
+                        let mut phases = Map::new();
+                        for (phase, regression) in Phase::ALL.into_iter().zip(regressions) {
+                            let mut stats = stats_json(timings.get(phase));
+                            stats["regression_percent"] = json!(regression.map(|(_, change)| change * 100.0));
+                            phases.insert(phase.name().to_string(), stats);
+                        }
+                        println!("{}", json!({ "year": day.year, "day": day.day, "input": input_json(day, &source), "iterations": iterations, "phases": phases }));
+                    }
+                }
+                baseline.update(day.year, day.day, &timings);
+                Ok(())
+            });
+
+            if n_regressions > 0 {
+                summary(format, &format!("{} regression(s) against {}", n_regressions, baseline_path.display()));
+            }
+            if save_baseline {
+                if let Err(e) = baseline.save(&baseline_path) {
+                    eprintln!("error: could not save baseline: {}", e);
+                    process::exit(2);
+                }
+                summary(format, &format!("Saved baseline to {}", baseline_path.display()));
+            }
+            failed
+        }
+        Command::Fetch { day } => fetch(year, day, format),
+        Command::Generate { day: Selection::All, variant: None, .. } => {
+            eprintln!("error: generating inputs for every day needs a --variant to save them as");
+            process::exit(2);
+        }
+        Command::Generate { day, size, seed, variant } => generate(&selected_days(year, day, &InputSource::Default), size, seed, variant.as_deref(), format),
+    };
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn selected_days(year: u16, day: Selection, source: &InputSource) -> Vec<&'static Day> {
+    match day {
+        Selection::All => {
+            if matches!(source, InputSource::Path(_) | InputSource::Stdin) {
+                eprintln!("error: --input can only be used with a single day");
+                process::exit(2);
+            }
+            registry::days(year).collect()
+        }
+        Selection::Day(day) => match registry::get(year, day) {
+            Some(day) => vec![day],
+            None => {
+                eprintln!("error: no solution for day {} of {}", day, year);
+                process::exit(2);
+            }
+        },
+    }
+}
+
+//Loads the input of every day and hands it to `f`; returns whether any input could not be read or parsed
+fn for_each_day(days: &[&Day], source: &InputSource, format: Format, mut f: impl FnMut(&Day, &str) -> Result<(), ParseError>) -> bool {
+    let (mut printed, mut failed) = (false, false);
+    for day in days {
+        let input = match input::load(day.year, day.day, source) {
+            Ok(input) => input,
+            Err(e) => {
+                report_failure(format, day, &format!("could not read input: {}", e));
+                failed = true;
+                continue;
+            }
+        };
+        if printed && format == Format::Text {
+            println!();
+        }
+        printed = true;
+        if let Err(e) = f(day, &input) {
+            report_failure(format, day, &format!("invalid input: {}", e));
+            failed = true;
+        }
+    }
+    failed
+}
+
+//Failures always go to stderr, and also end up in the JSON output so that no day goes missing from it
+fn report_failure(format: Format, day: &Day, error: &str) {
+    eprintln!("Day {:02}: {}", day.day, error);
+    if format == Format::Json {
+        println!("{}", json!({ "year": day.year, "day": day.day, "error": error }));
+    }
+}
+
+//A closing remark, which would not be valid JSON on stdout
+fn summary(format: Format, message: &str) {
+    match format {
+        Format::Text => println!("\n{}", message),
+        Format::Json => eprintln!("{}", message),
+    }
+}
+
+//Days with long searches keep checkpoints of them while solving, and `resume` picks them up from an earlier run
+fn run(day: &Day, part: Option<u8>, input: &str, source: &InputSource, format: Format, resume: bool, use_cache: bool) -> Result<(), ParseError> {
+    if format == Format::Text {
+        println!("Day {:02}", day.day);
+    }
+    let start = Instant::now();
+    let span = logging::span(format!("day{:02}", day.day));
+    let (answers, elapsed, cached) = solve_cached(day, input, part, use_cache, || match day.resume {
+        Some(solve) => solve(input, part, &|p| Checkpoint::open(checkpoint_path(day.year, day.day, p), day, input, resume)),
+        None => (day.solve)(input, part),
+    })?;
+    drop(span);
+    let total = start.elapsed();
+    match format {
+        Format::Text => {
+            if let Some(answer) = &answers.part_1 {
+                print_answer(1, answer);
+            }
+            if let Some(answer) = &answers.part_2 {
+                print_answer(2, answer);
+            }
+            match cached {
+                true => println!("Time: {:?} (cached)", total),
+                false => println!("Time: {:?}", total),
+            }
+        }
+        Format::Json => {
+            let mut json = answers_json(day, source, &answers, &elapsed, total);
+            json["cached"] = json!(cached);
+            println!("{}", json);
+        }
+    }
+    Ok(())
+}
+
+//Answers from the cache if the same solver solved this input before, or else solved and cached; tells which of the two
+fn solve_cached(day: &Day, input: &str, part: Option<u8>, use_cache: bool, solve: impl FnOnce() -> Result<(Answers, Elapsed), ParseError>) -> Result<(Answers, Elapsed, bool), ParseError> {
+    let cache = AnswerCache::new(AnswerCache::default_dir());
+    if let Some(answers) = use_cache.then(|| cache.get(day, input, part)).flatten() {
+        log::info!("answers taken from the cache");
+        return Ok((answers, Elapsed::default(), true));
+    }
+    let (answers, elapsed) = solve()?;
+    if let Err(e) = cache.put(day, input, &answers) {
+        log::warn!("could not cache the answers: {}", e);
+    }
+    Ok((answers, elapsed, false))
+}
+
+//Downloads the inputs that are not there yet; returns whether any download failed
+fn fetch(year: u16, day: Selection, format: Format) -> bool {
+    let days = match day {
+        Selection::All => (1..=25).collect(),
+        Selection::Day(day) => vec![day],
+    };
+    let fetcher = Fetcher::from_env();
+    let mut failed = false;
+    for day in days {
+        let (status, path) = match fetcher.fetch(year, day) {
+            Ok(Fetched::Cached(path)) => ("cached", path),
+            Ok(Fetched::Downloaded(path)) => ("downloaded", path),
+            Err(e) => {
+                eprintln!("Day {:02}: could not download input: {}", day, e);
+                if format == Format::Json {
+                    println!("{}", json!({ "year": year, "day": day, "error": e.to_string() }));
+                }
+                failed = true;
+                continue;
+            }
+        };
+        match format {
+            Format::Text => println!("Day {:02}: {} {}", day, status, path.display()),
+            Format::Json => println!("{}", json!({ "year": year, "day": day, "path": path.display().to_string(), "status": status })),
+        }
+    }
+    failed
+}
+
+//Times every phase on generated inputs of growing size, and how fast each phase grows with the size
+fn bench_sizes(days: &[&Day], sizes: &[usize], seed: u64, config: &Config, format: Format) -> bool {
+    let mut failed = false;
+    for (i, day) in days.iter().enumerate() {
+        if i > 0 && format == Format::Text {
+            println!();
+        }
+        let curve = sizes.iter().map(|&size| {
+            let input = (day.generate)(&mut Rng::new(seed), size);
+            (day.bench)(&input, config).map(|timings| (size, input.len(), timings))
+        }).collect::<Result<Vec<_>, ParseError>>();
+        let curve = match curve {
+            Ok(curve) => curve,
+            Err(e) => {
+                report_failure(format, day, &format!("rejected its generated input: {}", e));
+                failed = true;
+                continue;
+            }
+        };
+        let exponents = Phase::ALL.map(|phase| growth_exponent(&curve.iter().map(|(size, _, timings)| (*size, timings.get(phase).median)).collect_vec()));
+        match format {
+            Format::Text => {
+                println!("Day {:02}", day.day);
+                println!("{:<10}{:>12}{:>12}{:>12}{:>12}", "size", "bytes", "parse", "part1", "part2");
+                for (size, bytes, timings) in &curve {
+                    let [parse, part1, part2] = Phase::ALL.map(|phase| format!("{:.1?}", timings.get(phase).median));
+                    println!("{:<10}{:>12}{:>12}{:>12}{:>12}", size, bytes, parse, part1, part2);
+                }
+                let [parse, part1, part2] = exponents.map(|e| e.map_or("-".to_string(), |e| format!("n^{:.2}", e)));
+                println!("{:<10}{:>12}{:>12}{:>12}{:>12}", "growth", "", parse, part1, part2);
+            }
+            Format::Json => {
+                let points = curve.iter().map(|(size, bytes, timings)| {
+                    let phases = Phase::ALL.into_iter().map(|phase| (phase.name().to_string(), stats_json(timings.get(phase)))).collect::<Map<_, _>>();
+                    json!({ "size": size, "bytes": bytes, "phases": phases })
+                }).collect_vec();
+                let exponents = Phase::ALL.into_iter().zip(exponents).map(|(phase, e)| (phase.name().to_string(), json!(e))).collect::<Map<_, _>>();
+                println!("{}", json!({ "year": day.year, "day": day.day, "seed": seed, "iterations": config.iterations, "sizes": points, "growth_exponents": exponents }));
+            }
+        }
+    }
+    failed
+}
+
+//Prints a generated input, or saves it as a variant of every day's input; returns whether any could not be saved
+fn generate(days: &[&Day], size: usize, seed: u64, variant: Option<&str>, format: Format) -> bool {
+    let mut failed = false;
+    for day in days {
+        let input = (day.generate)(&mut Rng::new(seed), size);
+        let Some(variant) = variant else {
+            match format {
+                Format::Text => print!("{}", input),
+                Format::Json => println!("{}", json!({ "year": day.year, "day": day.day, "size": size, "seed": seed, "input": input })),
+            }
+            continue;
+        };
+        let path = input::input_path(day.year, day.day, Some(variant));
+        if let Err(e) = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::write(&path, &input)) {
+            report_failure(format, day, &format!("could not save input: {}: {}", path.display(), e));
+            failed = true;
+            continue;
+        }
+        match format {
+            Format::Text => println!("Day {:02}: saved {}", day.day, path.display()),
+            Format::Json => println!("{}", json!({ "year": day.year, "day": day.day, "size": size, "seed": seed, "path": path.display().to_string() })),
+        }
+    }
+    failed
+}
+
+//Shows the simulation of the requested part (or both parts) in the terminal, or writes its frames to a file
+fn watch(day: &Day, part: Option<u8>, input: &str, source: &InputSource, args: &VisualizeArgs) -> Result<(), ParseError> {
+    let record = day.visualize.expect("day has a visualization");
+    let parts = part.map_or(vec![1, 2], |part| vec![part]);
+    let show = |viewer: &mut dyn Viewer| parts.iter()
+        .try_for_each(|&part| record(input, part, &mut Recorder::new(&mut *viewer, args.every as usize)));
+    match &args.dump {
+        Some(path) => {
+            let file = File::create(path).unwrap_or_else(|e| {
+                eprintln!("error: could not create {}: {}", path.display(), e);
+                process::exit(2);
+            });
+            let mut dump = Dump::new(BufWriter::new(file));
+            show(&mut dump)?;
+            let written = dump.written();
+            if let Err(e) = dump.finish() {
+                eprintln!("error: could not write frames to {}: {}", path.display(), e);
+                process::exit(2);
+            }
+            eprintln!("Wrote {} frames to {}", written, path.display());
+        }
+        //Stdin cannot control the player when the input is read from it
+        None => show(&mut Player::new(args.fps, args.paused, !matches!(source, InputSource::Stdin)))?,
+    }
+    Ok(())
+}
+
+//Solves every day concurrently, then prints them in order with their status against the known answers; returns whether any day did not pass
+fn run_all(year: u16, part: Option<u8>, source: &InputSource, format: Format, use_cache: bool) -> bool {
+    let days = selected_days(year, Selection::All, source);
+    let manifest = match Manifest::load(year, &answers::manifest_path(year)) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("warning: no known answers to check against: {}", e);
+            Manifest::default()
+        }
+    };
+    let variant = match source {
+        InputSource::Variant(variant) => Some(variant.as_str()),
+        _ => None,
+    };
+
+    let start = Instant::now();
+    let context = logging::context();
+    let mut runs = days.par_iter().map(|day| context.run(|| {
+        let _span = logging::span(format!("day{:02}", day.day));
+        let start = Instant::now();
+        let solved = input::load(day.year, day.day, source).map_err(|e| format!("could not read input: {}", e))
+            .and_then(|input| solve_cached(day, &input, part, use_cache, || (day.solve)(&input, part)).map_err(|e| format!("invalid input: {}", e)));
+        (*day, solved.map(|(answers, elapsed, cached)| (answers, elapsed, start.elapsed(), cached)))
+    })).collect::<Vec<_>>();
+    let total = start.elapsed();
+    runs.sort_by_key(|(day, _)| day.day);
+
+    let mut rows = vec![];
+    let (mut pictures, mut mismatched) = (vec![], vec![]);
+    let mut counts = [0; 4];
+    for (day, solved) in runs.iter() {
+        let (status, row) = match solved {
+            Err(e) => {
+                eprintln!("Day {:02}: {}", day.day, e);
+                if format == Format::Json {
+                    println!("{}", json!({ "year": day.year, "day": day.day, "error": e, "status": "error" }));
+                }
+                (Status::Error, [format!("{:02}", day.day), "-".to_string(), "-".to_string(), "-".to_string()])
+            }
+            Ok((answers, elapsed, time, cached)) => {
+                let status = match manifest.get(day.year, day.day, variant) {
+                    None => Status::Unknown,
+                    Some(expected) => {
+                        let mismatches = answers::mismatches(expected, answers);
+                        for (part, expected, actual) in mismatches.iter() {
+                            mismatched.push(format!("Day {:02} part {}: expected {}, got {}", day.day, part, expected, actual));
+                        }
+                        match mismatches.is_empty() {
+                            true => Status::Pass,
+                            false => Status::Fail,
+                        }
+                    }
+                };
+                if format == Format::Json {
+                    let mut json = answers_json(day, source, answers, elapsed, *time);
+                    json["status"] = json!(status.name());
+                    json["cached"] = json!(cached);
+                    println!("{}", json);
+                }
+                let mut cell = |part: u8, answer: &Option<String>| match answer {
+                    Some(answer) if answer.contains('\n') => {
+                        pictures.push(format!("Day {:02} part {}:\n{}", day.day, part, answer));
+                        "(below)".to_string()
+                    }
+                    Some(answer) => answer.clone(),
+                    None => "-".to_string(),
+                };
+                let time = match cached {
+                    true => "cached".to_string(),
+                    false => format!("{:.1?}", time),
+                };
+                (status, [format!("{:02}", day.day), cell(1, &answers.part_1), cell(2, &answers.part_2), time])
+            }
+        };
+        counts[status as usize] += 1;
+        rows.push((row, status));
+    }
+
+    let summary_line = format!("{} days of {} in {:.1?}: {} passed, {} failed, {} unknown, {} errors",
+        runs.len(), year, total, counts[Status::Pass as usize], counts[Status::Fail as usize], counts[Status::Unknown as usize], counts[Status::Error as usize]);
+    match format {
+        Format::Text => {
+            print_table(&rows);
+            for text in pictures.iter().chain(mismatched.iter()) {
+                println!("\n{}", text);
+            }
+            println!("\n{}", summary_line);
+        }
+        Format::Json => eprintln!("{}", summary_line),
+    }
+    counts[Status::Fail as usize] + counts[Status::Error as usize] > 0
+}
+
+//How a day of `run all` compares to its known answers
+#[derive(Clone, Copy)]
+enum Status {
+    Pass,
+    Fail,
+    Unknown,
+    Error,
+}
+
+impl Status {
+    fn name(&self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Fail => "fail",
+            Status::Unknown => "unknown",
+            Status::Error => "error",
+        }
+    }
+}
+
+fn print_table(rows: &[([String; 4], Status)]) {
+    let header = ["day", "part 1", "part 2", "time"];
+    let widths = (0..4).map(|i| rows.iter().map(|(row, _)| row[i].chars().count()).chain([header[i].len()]).max().unwrap())
+        .collect::<Vec<usize>>();
+    let line = |cells: [&str; 4], status: &str| {
+        println!("{:<w0$}  {:<w1$}  {:<w2$}  {:>w3$}  {}", cells[0], cells[1], cells[2], cells[3], status,
+            w0 = widths[0], w1 = widths[1], w2 = widths[2], w3 = widths[3]);
+    };
+    line(header, "status");
+    for (row, status) in rows {
+        line([&row[0], &row[1], &row[2], &row[3]], status.name());
+    }
+}
+
+//Prints the stats of every phase, flagging the medians that regressed against the (baseline, change) next to them
+fn print_timings(timings: &Timings, regressions: &[Option<(Duration, f64)>; 3]) {
+    println!("{:<6}{:>12}{:>12}{:>12}{:>12}", "phase", "min", "median", "mean", "stddev");
+    for (phase, regression) in Phase::ALL.into_iter().zip(regressions) {
+        let stats = timings.get(phase);
+        let [min, median, mean, stddev] = [stats.min, stats.median, stats.mean, stats.stddev].map(|d| format!("{:.1?}", d));
+        print!("{:<6}{:>12}{:>12}{:>12}{:>12}", phase.name(), min, median, mean, stddev);
+        match regression {
+            Some((before, change)) => println!("  REGRESSION {:+.1}% (baseline {:.1?})", change * 100.0, before),
+            None => println!(),
+        }
+    }
+}
+
+fn answers_json(day: &Day, source: &InputSource, answers: &Answers, elapsed: &Elapsed, total: Duration) -> Value {
+    let time = json!({
+        "parse_ns": nanos(elapsed.parse),
+        "part1_ns": elapsed.part_1.map(nanos),
+        "part2_ns": elapsed.part_2.map(nanos),
+        "total_ns": nanos(total),
+    });
+    json!({
+        "year": day.year,
+        "day": day.day,
+        "input": input_json(day, source),
+        "part1": answers.part_1,
+        "part2": answers.part_2,
+        "time": time,
+    })
+}
+
+fn stats_json(stats: &Stats) -> Value {
+    json!({
+        "min_ns": nanos(stats.min),
+        "median_ns": nanos(stats.median),
+        "mean_ns": nanos(stats.mean),
+        "stddev_ns": nanos(stats.stddev),
+    })
+}
+
+//The variant and file (or "-" for stdin) a day's input was read from
+fn input_json(day: &Day, source: &InputSource) -> Value {
+    let (variant, path) = match source {
+        InputSource::Default => (None, input::input_path(day.year, day.day, None).display().to_string()),
+        InputSource::Variant(variant) => (Some(variant.as_str()), input::input_path(day.year, day.day, Some(variant)).display().to_string()),
+        InputSource::Path(path) => (None, path.display().to_string()),
+        InputSource::Stdin => (None, "-".to_string()),
+    };
+    json!({ "variant": variant, "path": path })
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos() as u64
+}
+
+fn print_answer(part: u8, answer: &str) {
+    match answer.contains('\n') {
+        true => println!("Part {}:\n{}", part, answer),
+        false => println!("Part {}: {}", part, answer),
+    }
+}
@@ -0,0 +1,60 @@
+use rayon::ThreadPoolBuilder;
+
+//Number of threads for the parallel solvers when --threads is not given
+pub const THREADS_VAR: &str = "AOC_THREADS";
+
+//Threads asked for with --threads, or else with AOC_THREADS (given its value, if it is set); None leaves one thread per
+//CPU
+pub fn requested_threads(flag: Option<usize>, var: Option<&str>) -> Result<Option<usize>, String> {
+    match (flag, var) {
+        (Some(threads), _) => Ok(Some(threads)),
+        (None, Some(threads)) => match threads.trim().parse::<usize>() {
+            Ok(threads) if threads > 0 => Ok(Some(threads)),
+            _ => Err(format!("{} should be a number of threads, not {:?}", THREADS_VAR, threads)),
+        },
+        (None, None) => Ok(None),
+    }
+}
+
+//Sizes the global rayon pool that every parallel solver runs on. With a single thread, work spawned in parallel runs one
+//piece after the other, giving the same results as running it sequentially
+pub fn init(threads: Option<usize>) -> Result<(), String> {
+    ThreadPoolBuilder::new().num_threads(threads.unwrap_or(0)).build_global().map_err(|e| e.to_string())
+}
+
+//Threads the parallel solvers split their work over: those of the rayon pool they run on
+pub fn n_threads() -> usize {
+    rayon::current_num_threads()
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::ThreadPoolBuilder;
+
+    use crate::generate::Rng;
+    use crate::registry;
+    use super::requested_threads;
+
+    #[test]
+    fn flag_takes_precedence_over_environment() {
+        assert_eq!(requested_threads(Some(1), Some("3")), Ok(Some(1)));
+        assert_eq!(requested_threads(None, Some(" 3\n")), Ok(Some(3)));
+        assert!(requested_threads(None, Some("none")).is_err());
+        assert!(requested_threads(None, Some("0")).is_err());
+        assert_eq!(requested_threads(None, None), Ok(None));
+    }
+
+    #[test]
+    fn one_thread_finds_the_answers_of_many() {
+        //Generated inputs, small enough to solve twice in a few seconds
+        for (day, size) in [(6, 1000), (16, 12), (19, 3)] {
+            let day = registry::DAYS.iter().find(|d| (d.year, d.day) == (2022, day)).unwrap();
+            let input = (day.generate)(&mut Rng::new(1), size);
+            let [one, many] = [1, 4].map(|threads| {
+                let pool = ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+                pool.install(|| (day.solve)(&input, None).unwrap().0)
+            });
+            assert_eq!(one, many, "day {}", day.day);
+        }
+    }
+}
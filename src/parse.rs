@@ -0,0 +1,288 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use itertools::Itertools;
+
+//Malformed input, located by line and column (both 1-based) in the text that was being parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+    pub found: String,
+    offset: usize,
+}
+
+impl ParseError {
+    //Error at `found`, which should be a slice of `source`
+    pub fn at(source: &str, found: &str, expected: impl Into<String>) -> Self {
+        Self::at_offset(source, offset_of(source, found), found, expected.into())
+    }
+
+    //Error for `source` ending while `expected` was still missing
+    pub fn end(source: &str, expected: impl Into<String>) -> Self {
+        Self::at(source, &source[source.len()..], expected)
+    }
+
+    //Moves an error found in `chunk`, a slice of `source`, to its position in `source`
+    pub fn within(self, source: &str, chunk: &str) -> Self {
+        let offset = offset_of(source, chunk) + self.offset;
+        Self::at_offset(source, offset, &self.found, self.expected)
+    }
+
+    fn at_offset(source: &str, offset: usize, found: &str, expected: String) -> Self {
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[offset..].find('\n').map_or(source.len(), |i| offset + i);
+        ParseError {
+            line: source[..offset].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+            text: source[line_start..line_end].trim_end_matches('\r').to_string(),
+            expected,
+            found: found.to_string(),
+            offset,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: expected {}, found ", self.line, self.column, self.expected)?;
+        match self.found.is_empty() {
+            true => write!(f, "nothing")?,
+            false => write!(f, "{:?}", self.found)?,
+        }
+        write!(f, "\n    {}\n    {}^", self.text, " ".repeat(self.column - 1))
+    }
+}
+
+impl Error for ParseError {}
+
+//Parses `field`, a slice of `source`, reporting where it is when it is missing or malformed
+pub fn parse_field<T: FromStr>(source: &str, field: Option<&str>, expected: &str) -> Result<T, ParseError> {
+    let field = field.ok_or_else(|| ParseError::end(source, expected))?;
+    field.parse().map_err(|_| ParseError::at(source, field, expected))
+}
+
+//Whitespace allowed before tokens. Carriage returns are not part of it, so that like with `parse_to_vec` a stray one
+//is reported instead of being read past
+const BLANKS: [char; 3] = [' ', '\t', '\n'];
+
+//Reads a piece of text from left to right, one token at a time. Tokens may be preceded by whitespace, and every error
+//points at the token where the text stopped looking as expected
+#[derive(Debug, Clone, Copy)]
+pub struct Scanner<'a> {
+    source: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Scanner { source, rest: source }
+    }
+
+    //Text not read yet
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+
+    //Whether only whitespace is left
+    pub fn is_empty(&self) -> bool {
+        self.rest.trim_matches(BLANKS).is_empty()
+    }
+
+    //Reads `literal`, which has to come next
+    pub fn literal(&mut self, literal: &str) -> Result<(), ParseError> {
+        match self.eat(literal) {
+            true => Ok(()),
+            false => Err(self.error(format!("{:?}", literal))),
+        }
+    }
+
+    //Reads `literal` if it comes next, and tells whether it did. A literal ending in a letter is not read from the
+    //start of a longer word ("to" is not in "tomato"), but may be followed by a number ("R" is in "R5")
+    pub fn eat(&mut self, literal: &str) -> bool {
+        let is_letter = |c: char| c.is_alphabetic() || c == '_';
+        match self.rest.trim_start_matches(BLANKS).strip_prefix(literal) {
+            Some(rest) if !(literal.ends_with(is_letter) && rest.starts_with(is_letter)) => {
+                self.rest = rest;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    //Reads whichever of `options` comes next, trying them in order
+    pub fn one_of<'o>(&mut self, options: &[&'o str]) -> Result<&'o str, ParseError> {
+        options.iter().find(|option| self.eat(option)).copied()
+            .ok_or_else(|| self.error(options.iter().map(|option| format!("{:?}", option)).join(" or ")))
+    }
+
+    //Reads a whole number, with an optional sign, that fits in a `T`. Nothing is read if there is none
+    pub fn integer<T: FromStr>(&mut self, expected: &str) -> Result<T, ParseError> {
+        let start = *self;
+        let token = self.take_while(|i, c| c.is_ascii_digit() || (i == 0 && (c == '-' || c == '+')));
+        token.parse().map_err(|_| {
+            *self = start;
+            start.error(expected)
+        })
+    }
+
+    //Reads a name made of letters, digits and underscores
+    pub fn identifier(&mut self, expected: &str) -> Result<&'a str, ParseError> {
+        let token = self.take_while(|_, c| c.is_alphanumeric() || c == '_');
+        match token.is_empty() {
+            true => Err(self.error(expected)),
+            false => Ok(token),
+        }
+    }
+
+    //Reads one or more items, with `separator` between each two of them
+    pub fn separated<T>(&mut self, separator: &str, mut item: impl FnMut(&mut Self) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+        let mut items = vec![item(self)?];
+        while self.eat(separator) {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    //Checks that only whitespace is left
+    pub fn end(&self) -> Result<(), ParseError> {
+        match self.is_empty() {
+            true => Ok(()),
+            false => Err(self.error("nothing more")),
+        }
+    }
+
+    //Error at the next word, or at the end of the text if there is none
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        let rest = self.rest.trim_start_matches(BLANKS);
+        ParseError::at(self.source, rest.split(BLANKS).next().unwrap_or(rest), expected)
+    }
+
+    //Reads the longest prefix (after whitespace) whose characters all pass `f`, given their index and themselves
+    fn take_while(&mut self, f: impl Fn(usize, char) -> bool) -> &'a str {
+        let rest = self.rest.trim_start_matches(BLANKS);
+        let end = rest.char_indices().enumerate().find(|&(i, (_, c))| !f(i, c)).map_or(rest.len(), |(_, (at, _))| at);
+        let (token, rest_after) = rest.split_at(end);
+        self.rest = match token.is_empty() {
+            true => self.rest,
+            false => rest_after,
+        };
+        token
+    }
+}
+
+//Parts of `input` separated by blank lines, without the line breaks around them
+pub fn sections(input: &str) -> Vec<&str> {
+    let (mut sections, mut start, mut end, mut offset) = (vec![], None, 0, 0);
+    for line in input.split_inclusive('\n') {
+        match (line.trim().is_empty(), start) {
+            (false, None) => start = Some(offset),
+            (true, Some(section_start)) => {
+                sections.push(&input[section_start..end]);
+                start = None;
+            }
+            _ => {}
+        }
+        if !line.trim().is_empty() {
+            end = offset + line.trim_end_matches(['\r', '\n']).len();
+        }
+        offset += line.len();
+    }
+    sections.extend(start.map(|section_start| &input[section_start..end]));
+    sections
+}
+
+//Byte offset of `slice` in `source`, falling back to a search if it was not sliced from it
+fn offset_of(source: &str, slice: &str) -> usize {
+    let (start, at) = (source.as_ptr() as usize, slice.as_ptr() as usize);
+    match start <= at && at + slice.len() <= start + source.len() {
+        true => at - start,
+        false => source.find(slice).unwrap_or(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_field, sections, ParseError, Scanner};
+
+    #[test]
+    fn errors_point_at_the_offending_text() {
+        let input = "move 1 from 2 to 3\nmove x from 1 to 2\n";
+        let line = input.lines().nth(1).unwrap();
+        let err = parse_field::<usize>(line, line.split(' ').nth(1), "a crate count").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 6));
+
+        let err = err.within(input, line);
+        assert_eq!((err.line, err.column), (2, 6));
+        assert_eq!(err.text, "move x from 1 to 2");
+        assert_eq!(err.found, "x");
+        assert_eq!(err.to_string(), "line 2, column 6: expected a crate count, found \"x\"\n    move x from 1 to 2\n         ^");
+
+        let err = ParseError::end(line, "a destination");
+        assert_eq!((err.line, err.column, err.found.as_str()), (1, 19, ""));
+    }
+
+    #[test]
+    fn scanner_reads_tokens_and_points_at_the_first_unexpected_one() {
+        let input = "Valve AA has flow rate=-3; tunnels lead to valves DD, II,BB\n";
+        let mut s = Scanner::new(input);
+        s.literal("Valve").unwrap();
+        assert_eq!(s.identifier("a valve").unwrap(), "AA");
+        s.literal("has flow rate=").unwrap();
+        assert_eq!(s.integer::<i32>("a flow rate").unwrap(), -3);
+        s.literal(";").unwrap();
+        assert_eq!(s.one_of(&["tunnel leads to valve", "tunnels lead to valves"]).unwrap(), "tunnels lead to valves");
+        assert_eq!(s.separated(",", |s| s.identifier("a valve")).unwrap(), ["DD", "II", "BB"]);
+        assert!(s.end().is_ok());
+
+        let mut s = Scanner::new("move 1 from x0 to 3");
+        s.literal("move").unwrap();
+        assert!(!s.eat("to"));
+        assert_eq!(s.integer::<u8>("a count").unwrap(), 1);
+        s.literal("from").unwrap();
+        let err = s.integer::<u8>("a stack").unwrap_err();
+        assert_eq!((err.column, err.found.as_str(), err.expected.as_str()), (13, "x0", "a stack"));
+        let err = Scanner::new("move 300").integer::<u8>("a count").unwrap_err();
+        assert_eq!((err.column, err.found.as_str()), (1, "move"));
+        let mut s = Scanner::new("1 2");
+        assert_eq!(s.separated(",", |s| s.integer::<u8>("a number")).unwrap(), [1]);
+        assert_eq!(s.end().unwrap_err().found, "2");
+
+        let mut s = Scanner::new("  12");
+        s.integer::<u8>("a number").unwrap();
+        let err = s.literal("R").unwrap_err();
+        assert_eq!((err.column, err.found.as_str(), err.expected.as_str()), (5, "", "\"R\""));
+    }
+
+    #[test]
+    fn scanner_stays_put_when_a_token_is_not_there() {
+        let mut s = Scanner::new("tomato 300 to");
+        assert!(!s.eat("to"));
+        assert!(s.literal("to").is_err());
+        s.literal("tomato").unwrap();
+        assert!(s.integer::<u8>("a small number").is_err());
+        assert_eq!(s.integer::<u16>("a number").unwrap(), 300);
+        s.literal("to").unwrap();
+        assert!(s.end().is_ok());
+
+        let mut s = Scanner::new("R5 -x");
+        assert_eq!(s.one_of(&["L", "R"]).unwrap(), "R");
+        assert_eq!(s.integer::<u8>("a distance").unwrap(), 5);
+        assert!(s.integer::<i8>("a number").is_err());
+        assert_eq!(s.rest(), " -x");
+
+        let mut s = Scanner::new("4\r\n");
+        s.integer::<u8>("a number").unwrap();
+        assert_eq!(s.end().unwrap_err().found, "\r");
+    }
+
+    #[test]
+    fn sections_are_split_on_blank_lines() {
+        let input = "1\n2\n\n3\r\n\r\n\n  4\n";
+        assert_eq!(sections(input), ["1\n2", "3", "  4"]);
+        assert!(sections("\n\n").is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+//Position on a 2D map, where x grows to the right and y grows downwards like the rows of a Grid
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point2 {
+    pub x: i32,
+    pub y: i32,
+}
+
+//Position in 3D space
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3 {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Point2 {
+    pub const ORIGIN: Point2 = Point2 { x: 0, y: 0 };
+
+    pub const fn new(x: i32, y: i32) -> Self {
+        Point2 { x, y }
+    }
+
+    pub fn manhattan(self, other: Point2) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    //Number of king moves between both points
+    pub fn chebyshev(self, other: Point2) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    //One step towards the same direction along every axis
+    pub fn signum(self) -> Self {
+        Point2::new(self.x.signum(), self.y.signum())
+    }
+
+    //Quarter turn around the origin
+    pub fn rotate_cw(self) -> Self {
+        Point2::new(-self.y, self.x)
+    }
+
+    pub fn rotate_ccw(self) -> Self {
+        Point2::new(self.y, -self.x)
+    }
+
+    //Horizontally and vertically adjacent points, clockwise starting above
+    pub fn neighbors4(self) -> [Point2; 4] {
+        [(0, -1), (1, 0), (0, 1), (-1, 0)].map(|(dx, dy)| self + Point2::new(dx, dy))
+    }
+
+    //Horizontally, vertically and diagonally adjacent points, clockwise starting above
+    pub fn neighbors8(self) -> [Point2; 8] {
+        [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)].map(|(dx, dy)| self + Point2::new(dx, dy))
+    }
+}
+
+impl Point3 {
+    pub const ORIGIN: Point3 = Point3 { x: 0, y: 0, z: 0 };
+
+    pub const fn new(x: i32, y: i32, z: i32) -> Self {
+        Point3 { x, y, z }
+    }
+
+    pub fn manhattan(self, other: Point3) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    pub fn chebyshev(self, other: Point3) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs()).max((self.z - other.z).abs())
+    }
+
+    pub fn signum(self) -> Self {
+        Point3::new(self.x.signum(), self.y.signum(), self.z.signum())
+    }
+
+    //Points sharing a face with this one
+    pub fn neighbors6(self) -> [Point3; 6] {
+        [(1, 0, 0), (-1, 0, 0), (0, 1, 0), (0, -1, 0), (0, 0, 1), (0, 0, -1)].map(|(dx, dy, dz)| self + Point3::new(dx, dy, dz))
+    }
+
+    //Points sharing a face, an edge or a corner with this one
+    pub fn neighbors26(self) -> [Point3; 26] {
+        let mut neighbors = [self; 26];
+        let offsets = (-1..=1).flat_map(|dx| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| Point3::new(dx, dy, dz))));
+        for (neighbor, offset) in neighbors.iter_mut().zip(offsets.filter(|&o| o != Point3::ORIGIN)) {
+            *neighbor += offset;
+        }
+        neighbors
+    }
+}
+
+//Componentwise arithmetic, and scaling by a factor
+macro_rules! point_ops {
+    ($point:ident { $($axis:ident),* }) => {
+        impl Add for $point {
+            type Output = $point;
+
+            fn add(self, other: $point) -> $point {
+                $point { $($axis: self.$axis + other.$axis),* }
+            }
+        }
+
+        impl Sub for $point {
+            type Output = $point;
+
+            fn sub(self, other: $point) -> $point {
+                $point { $($axis: self.$axis - other.$axis),* }
+            }
+        }
+
+        impl Mul<i32> for $point {
+            type Output = $point;
+
+            fn mul(self, factor: i32) -> $point {
+                $point { $($axis: self.$axis * factor),* }
+            }
+        }
+
+        impl Neg for $point {
+            type Output = $point;
+
+            fn neg(self) -> $point {
+                $point { $($axis: -self.$axis),* }
+            }
+        }
+
+        impl AddAssign for $point {
+            fn add_assign(&mut self, other: $point) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $point {
+            fn sub_assign(&mut self, other: $point) {
+                *self = *self - other;
+            }
+        }
+    };
+}
+
+point_ops!(Point2 { x, y });
+point_ops!(Point3 { x, y, z });
+
+#[cfg(test)]
+mod tests {
+    use super::{Point2, Point3};
+
+    #[test]
+    fn points_measure_and_neighbor() {
+        let (a, b) = (Point2::new(1, -2), Point2::new(4, 2));
+        assert_eq!(b - a, Point2::new(3, 4));
+        assert_eq!(a + (b - a) * 2, Point2::new(7, 6));
+        assert_eq!((a.manhattan(b), a.chebyshev(b)), (7, 4));
+        assert_eq!((b - a).signum(), Point2::new(1, 1));
+        assert_eq!(Point2::new(0, -1).rotate_cw(), Point2::new(1, 0));
+        assert_eq!(Point2::new(1, 0).rotate_ccw(), Point2::new(0, -1));
+        assert_eq!(Point2::ORIGIN.neighbors4()[1], Point2::new(1, 0));
+        assert!(Point2::ORIGIN.neighbors8().iter().all(|n| n.chebyshev(Point2::ORIGIN) == 1));
+
+        let c = Point3::new(1, 2, 3);
+        assert_eq!(-c, Point3::new(-1, -2, -3));
+        assert_eq!((c.manhattan(Point3::ORIGIN), c.chebyshev(Point3::ORIGIN)), (6, 3));
+        assert!(c.neighbors6().iter().all(|n| n.manhattan(c) == 1));
+        let mut neighbors = c.neighbors26();
+        neighbors.sort();
+        neighbors.windows(2).for_each(|w| assert_ne!(w[0], w[1]));
+        assert!(neighbors.iter().all(|n| n.chebyshev(c) == 1));
+    }
+}
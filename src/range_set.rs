@@ -0,0 +1,156 @@
+use std::ops::RangeInclusive;
+
+use num::PrimInt;
+
+//Set of integers stored as sorted, disjoint ranges; touching ranges are merged into one
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    ranges: Vec<RangeInclusive<T>>,
+}
+
+impl<T: PrimInt> Default for RangeSet<T> {
+    fn default() -> Self {
+        RangeSet { ranges: vec![] }
+    }
+}
+
+impl<T: PrimInt> RangeSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        //Ranges that overlap or touch the new one are absorbed by it
+        let from = self.ranges.partition_point(|r| *r.end() < start && *r.end() + T::one() < start);
+        let to = self.ranges.partition_point(|r| *r.start() <= end || *r.start() - T::one() <= end);
+        if from < to {
+            start = start.min(*self.ranges[from].start());
+            end = end.max(*self.ranges[to - 1].end());
+        }
+        self.ranges.splice(from..to, [start..=end]);
+    }
+
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        let from = self.ranges.partition_point(|r| *r.end() < start);
+        let to = self.ranges.partition_point(|r| *r.start() <= end);
+        if from == to {
+            return;
+        }
+        //Parts of the first and last overlapping range that stick out remain
+        let (first, last) = (*self.ranges[from].start(), *self.ranges[to - 1].end());
+        let remaining = [(first < start).then(|| first..=start - T::one()), (last > end).then(|| end + T::one()..=last)];
+        self.ranges.splice(from..to, remaining.into_iter().flatten());
+    }
+
+    pub fn union(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut union = self.clone();
+        other.iter().for_each(|r| union.insert(r));
+        union
+    }
+
+    pub fn intersection(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let (mut i, mut j, mut ranges) = (0, 0, vec![]);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let (start, end) = (*a.start().max(b.start()), *a.end().min(b.end()));
+            if start <= end {
+                ranges.push(start..=end);
+            }
+            match a.end() < b.end() {
+                true => i += 1,
+                false => j += 1,
+            }
+        }
+        RangeSet { ranges }
+    }
+
+    pub fn difference(&self, other: &RangeSet<T>) -> RangeSet<T> {
+        let mut difference = self.clone();
+        other.iter().for_each(|r| difference.remove(r));
+        difference
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| *r.end() < value);
+        self.ranges.get(i).is_some_and(|r| *r.start() <= value)
+    }
+
+    //Number of values in the set, which for a full range of 64 bit values is one more than u64 holds.
+    //Only counts sets of integers up to 64 bits wide
+    pub fn len(&self) -> u128 {
+        self.ranges.iter().map(|r| (r.end().to_i128().unwrap() - r.start().to_i128().unwrap()) as u128 + 1).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    //Ranges of values within `bounds` that are not in the set
+    pub fn gaps(&self, bounds: RangeInclusive<T>) -> RangeSet<T> {
+        RangeSet::from(bounds).difference(self)
+    }
+
+    //The ranges making up the set, in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().cloned()
+    }
+}
+
+impl<T: PrimInt> From<RangeInclusive<T>> for RangeSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let mut set = RangeSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = RangeSet::new();
+        iter.into_iter().for_each(|r| set.insert(r));
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeInclusive;
+
+    use super::RangeSet;
+
+    #[test]
+    fn range_sets_merge_and_split() {
+        let mut set = [5..=7, 1..=2, 3..=3, 10..=12, RangeInclusive::new(9, 8)].into_iter().collect::<RangeSet<i32>>();
+        assert_eq!(set.iter().collect::<Vec<_>>(), [1..=3, 5..=7, 10..=12]);
+        assert_eq!(set.len(), 9);
+        assert!(set.contains(6) && !set.contains(4) && !set.contains(13));
+
+        set.insert(4..=9);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [1..=12]);
+        set.remove(3..=5);
+        set.remove(12..=20);
+        assert_eq!(set.iter().collect::<Vec<_>>(), [1..=2, 6..=11]);
+        assert_eq!(set.gaps(0..=15).iter().collect::<Vec<_>>(), [0..=0, 3..=5, 12..=15]);
+
+        let other = [0..=1, 5..=7, 11..=11].into_iter().collect::<RangeSet<i32>>();
+        assert_eq!(set.union(&other).iter().collect::<Vec<_>>(), [0..=2, 5..=11]);
+        assert_eq!(set.intersection(&other).iter().collect::<Vec<_>>(), [1..=1, 6..=7, 11..=11]);
+        assert_eq!(set.difference(&other).iter().collect::<Vec<_>>(), [2..=2, 8..=10]);
+        assert!(set.difference(&set).is_empty());
+
+        let full = RangeSet::from(u8::MIN..=u8::MAX);
+        assert_eq!(full.len(), 256);
+        assert_eq!(full.gaps(0..=255), RangeSet::new());
+        assert_eq!(RangeSet::from(i64::MIN..=i64::MAX).len(), 1 << 64);
+        let halves = [0..=u64::MAX / 2, u64::MAX / 2 + 2..=u64::MAX].into_iter().collect::<RangeSet<u64>>();
+        assert_eq!(halves.len(), u64::MAX as u128);
+    }
+}
@@ -0,0 +1,103 @@
+use itertools::Itertools;
+
+use crate::*;
+use crate::bench::{time_phases, Config, Timings};
+use crate::checkpoint::{solve_resumable, Checkpoint};
+use crate::generate::{Generate, Rng};
+use crate::solution::{parse, solve, Answers, Elapsed};
+use crate::visualize::{record, Recorder};
+
+//Solves the requested part of an input (or both), timing every stage
+pub type SolveFn = fn(&str, Option<u8>) -> Result<(Answers, Elapsed), ParseError>;
+
+//Solves like a `SolveFn`, keeping the progress of each part in the checkpoint opened for it
+pub type ResumeFn = fn(&str, Option<u8>, &dyn Fn(u8) -> Checkpoint) -> Result<(Answers, Elapsed), ParseError>;
+
+//Runs the simulation of a part of an input, showing its steps to the recorder
+pub type VisualizeFn = fn(&str, u8, &mut Recorder) -> Result<(), ParseError>;
+
+//Type-erased solver of a single puzzle day of a year
+pub struct Day {
+    pub year: u16,
+    pub day: u8,
+    pub version: &'static str,
+    //Source of the modules the solver is written in, so that answers cached before they were edited are not used
+    pub sources: &'static [&'static str],
+    pub solve: SolveFn,
+    pub parse: fn(&str) -> Result<(), ParseError>,
+    pub bench: fn(&str, &Config) -> Result<Timings, ParseError>,
+    pub visualize: Option<VisualizeFn>,
+    pub resume: Option<ResumeFn>,
+    pub generate: fn(&mut Rng, usize) -> String,
+}
+
+macro_rules! day {
+    ($year:expr, $day:expr, $module:ident::$solution:ident) => {
+        day!($year, $day, $module::$solution, sources [$module])
+    };
+    ($year:expr, $day:expr, $module:ident::$solution:ident, sources [$($source:ident),+]) => {
+        Day {
+            year: $year,
+            day: $day,
+            version: <$module::$solution as Solution>::VERSION,
+            sources: &[$(include_str!(concat!(stringify!($source), ".rs"))),+],
+            solve: solve::<$module::$solution>,
+            parse: parse::<$module::$solution>,
+            bench: time_phases::<$module::$solution>,
+            visualize: None,
+            resume: None,
+            generate: <$module::$solution as Generate>::generate,
+        }
+    };
+    ($year:expr, $day:expr, $module:ident::$solution:ident, visualize) => {
+        Day { visualize: Some(record::<$module::$solution>), ..day!($year, $day, $module::$solution) }
+    };
+    ($year:expr, $day:expr, $module:ident::$solution:ident, resume) => {
+        Day { resume: Some(solve_resumable::<$module::$solution>), ..day!($year, $day, $module::$solution) }
+    };
+}
+
+//Every solved day, ordered by year and day
+pub const DAYS: &[Day] = &[
+    day!(2022, 1, day01::Day01),
+    day!(2022, 2, day02::Day02),
+    day!(2022, 3, day03::Day03),
+    day!(2022, 4, day04::Day04),
+    day!(2022, 5, day05::Day05),
+    day!(2022, 6, day06::Day06),
+    day!(2022, 7, day07::Day07),
+    day!(2022, 8, day08::Day08),
+    day!(2022, 9, day09::Day09),
+    day!(2022, 10, day10::Day10),
+    day!(2022, 11, day11::Day11),
+    day!(2022, 12, day12::Day12),
+    day!(2022, 13, day13::Day13),
+    day!(2022, 14, day14::Day14, visualize),
+    day!(2022, 15, day15::Day15),
+    day!(2022, 16, day16::Day16, resume),
+    day!(2022, 17, day17::Day17, visualize),
+    day!(2022, 18, day18::Day18),
+    day!(2022, 19, day19::Day19, resume),
+    day!(2022, 20, day20::Day20),
+    day!(2022, 21, day21::Day21),
+    day!(2022, 22, day22::Day22, sources [day22, day22_2]),
+    day!(2022, 23, day23::Day23, visualize),
+    day!(2022, 24, day24::Day24, visualize),
+    day!(2022, 25, day25::Day25),
+];
+
+pub fn get(year: u16, day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.year == year && d.day == day)
+}
+
+pub fn days(year: u16) -> impl Iterator<Item = &'static Day> {
+    DAYS.iter().filter(move |d| d.year == year)
+}
+
+pub fn years() -> Vec<u16> {
+    DAYS.iter().map(|d| d.year).dedup().collect()
+}
+
+pub fn latest_year() -> u16 {
+    DAYS.iter().map(|d| d.year).max().unwrap()
+}
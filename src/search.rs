@@ -0,0 +1,185 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+
+use fxhash::FxHashMap;
+
+//A graph given by the nodes one step away from every node, along with the cost of that step
+pub trait Neighbors<N> {
+    fn neighbors(&self, node: &N) -> impl Iterator<Item = (N, usize)>;
+}
+
+impl<N, F, I> Neighbors<N> for F where F: Fn(&N) -> I, I: IntoIterator<Item = (N, usize)> {
+    fn neighbors(&self, node: &N) -> impl Iterator<Item = (N, usize)> {
+        self(node).into_iter()
+    }
+}
+
+//Cheapest path found to a node, from one of the starts of the search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N> {
+    pub cost: usize,
+    pub nodes: Vec<N>,
+}
+
+//Every node a search reached, with its cost and the node it was reached from
+#[derive(Debug, Clone)]
+pub struct Explored<N> {
+    costs: FxHashMap<N, usize>,
+    predecessors: FxHashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash> Explored<N> {
+    fn new() -> Self {
+        Explored { costs: FxHashMap::default(), predecessors: FxHashMap::default() }
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.costs.contains_key(node)
+    }
+
+    pub fn cost(&self, node: &N) -> Option<usize> {
+        self.costs.get(node).copied()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.costs.keys()
+    }
+
+    pub fn path_to(&self, node: &N) -> Option<Path<N>> {
+        let cost = self.cost(node)?;
+        let mut nodes = vec![node.clone()];
+        while let Some(previous) = self.predecessors.get(nodes.last().unwrap()) {
+            nodes.push(previous.clone());
+        }
+        nodes.reverse();
+        Some(Path { cost, nodes })
+    }
+}
+
+//Breadth-first search for the nearest goal, counting steps and ignoring their costs
+pub fn bfs<N, G>(graph: &G, starts: impl IntoIterator<Item = N>, goal: impl FnMut(&N) -> bool) -> Option<Path<N>>
+    where N: Clone + Eq + Hash, G: Neighbors<N> {
+    let (explored, found) = breadth_first(graph, starts, goal);
+    found.and_then(|node| explored.path_to(&node))
+}
+
+//Every node reachable from the starts, with the number of steps to get there
+pub fn explore<N, G>(graph: &G, starts: impl IntoIterator<Item = N>) -> Explored<N>
+    where N: Clone + Eq + Hash, G: Neighbors<N> {
+    breadth_first(graph, starts, |_| false).0
+}
+
+fn breadth_first<N, G>(graph: &G, starts: impl IntoIterator<Item = N>, mut goal: impl FnMut(&N) -> bool) -> (Explored<N>, Option<N>)
+    where N: Clone + Eq + Hash, G: Neighbors<N> {
+    let mut explored = Explored::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if explored.costs.insert(start.clone(), 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        if goal(&node) {
+            log::debug!("breadth-first search reached a goal after exploring {} nodes", explored.costs.len());
+            return (explored, Some(node));
+        }
+        let steps = explored.costs[&node] + 1;
+        for (next, _) in graph.neighbors(&node) {
+            if !explored.contains(&next) {
+                explored.costs.insert(next.clone(), steps);
+                explored.predecessors.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    log::debug!("breadth-first search explored all {} reachable nodes", explored.costs.len());
+    (explored, None)
+}
+
+//Cheapest path to a goal
+pub fn dijkstra<N, G>(graph: &G, starts: impl IntoIterator<Item = N>, goal: impl FnMut(&N) -> bool) -> Option<Path<N>>
+    where N: Clone + Eq + Hash, G: Neighbors<N> {
+    astar(graph, starts, goal, |_| 0)
+}
+
+//Cheapest path to a goal, guided by a heuristic that never overestimates the remaining cost
+pub fn astar<N, G>(graph: &G, starts: impl IntoIterator<Item = N>, mut goal: impl FnMut(&N) -> bool, mut heuristic: impl FnMut(&N) -> usize) -> Option<Path<N>>
+    where N: Clone + Eq + Hash, G: Neighbors<N> {
+    let mut explored = Explored::new();
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        explored.costs.insert(start.clone(), 0);
+        queue.push(Queued { priority: heuristic(&start), cost: 0, node: start });
+    }
+    while let Some(Queued { cost, node, .. }) = queue.pop() {
+        if cost > explored.costs[&node] {
+            continue; //a cheaper way to this node was found after queueing it
+        }
+        if goal(&node) {
+            log::debug!("A* reached a goal at cost {} after exploring {} nodes, {} still queued", cost, explored.costs.len(), queue.len());
+            return explored.path_to(&node);
+        }
+        for (next, step) in graph.neighbors(&node) {
+            let next_cost = cost + step;
+            if explored.cost(&next).is_none_or(|known| next_cost < known) {
+                explored.costs.insert(next.clone(), next_cost);
+                explored.predecessors.insert(next.clone(), node.clone());
+                queue.push(Queued { priority: next_cost + heuristic(&next), cost: next_cost, node: next });
+            }
+        }
+    }
+    log::debug!("A* explored all {} reachable nodes without reaching a goal", explored.costs.len());
+    None
+}
+
+//Entry of the priority queue, ordered so the lowest priority (and then the highest cost) comes out first
+struct Queued<N> {
+    priority: usize,
+    cost: usize,
+    node: N,
+}
+
+impl<N> Ord for Queued<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority).then(self.cost.cmp(&other.cost))
+    }
+}
+
+impl<N> PartialOrd for Queued<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> PartialEq for Queued<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N> Eq for Queued<N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{astar, bfs, dijkstra, explore};
+
+    #[test]
+    fn searches_find_cheapest_paths() {
+        //0 -1- 1 -1- 2 -1- 3, with a direct but expensive 0 -5- 3
+        let edges = [vec![(1, 1), (3, 5)], vec![(0, 1), (2, 1)], vec![(1, 1), (3, 1)], vec![(2, 1), (0, 5)]];
+        let graph = |n: &usize| edges[*n].clone();
+
+        let path = bfs(&graph, [0], |n| *n == 3).unwrap();
+        assert_eq!((path.cost, path.nodes), (1, vec![0, 3]));
+        let path = dijkstra(&graph, [0], |n| *n == 3).unwrap();
+        assert_eq!((path.cost, path.nodes), (3, vec![0, 1, 2, 3]));
+        let path = astar(&graph, [0], |n| *n == 3, |n| 3 - n).unwrap();
+        assert_eq!(path.cost, 3);
+
+        let path = bfs(&graph, [3, 1], |n| *n == 2).unwrap();
+        assert_eq!(path.nodes.len(), 2);
+        assert_eq!(dijkstra(&graph, [0], |n| *n == 4), None);
+        assert_eq!(explore(&graph, [2]).cost(&0), Some(2));
+    }
+}
@@ -0,0 +1,125 @@
+use std::time::{Duration, Instant};
+
+use crate::logging::span;
+use crate::ParseError;
+
+//A puzzle day, split into its parsing stage and both parts
+pub trait Solution {
+    type Input;
+    type Part1: Answer;
+    type Part2: Answer;
+    //Changed whenever the answers of the solver may change through anything but the source of its own modules and of the
+    //library modules the solvers share, like a file it includes, so that answers cached by an older version are not used.
+    //Edits to those sources change the version by themselves
+    const VERSION: &'static str = "1";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Part1;
+    fn part2(input: &Self::Input) -> Self::Part2;
+}
+
+//What a part finds. A part that can only tell while solving that an input has no answer returns a Result, with an error
+//located in the input like the ones found while parsing, or a NoAnswer when no single spot of the input is to blame
+pub trait Answer {
+    fn answer(self, source: &str) -> Result<String, ParseError>;
+}
+
+//What an input without an answer would need, reported at the end of the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoAnswer(pub String);
+
+macro_rules! answers_shown_as_they_are {
+    ($($answer:ty),+) => {
+        $(impl Answer for $answer {
+            fn answer(self, _source: &str) -> Result<String, ParseError> {
+                Ok(self.to_string())
+            }
+        })+
+    };
+}
+
+answers_shown_as_they_are!(i32, i64, u32, u64, usize, String, &str);
+
+impl<T: Answer> Answer for Result<T, ParseError> {
+    fn answer(self, source: &str) -> Result<String, ParseError> {
+        self?.answer(source)
+    }
+}
+
+impl<T: Answer> Answer for Result<T, NoAnswer> {
+    fn answer(self, source: &str) -> Result<String, ParseError> {
+        match self {
+            Ok(answer) => answer.answer(source),
+            Err(NoAnswer(expected)) => Err(ParseError::end(source, expected)),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers {
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+//Time taken by every stage that ran
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed {
+    pub parse: Duration,
+    pub part_1: Option<Duration>,
+    pub part_2: Option<Duration>,
+}
+
+//Parses the input once and solves the requested part (or both parts if none is given)
+pub fn solve<S: Solution>(input: &str, part: Option<u8>) -> Result<(Answers, Elapsed), ParseError> {
+    solve_with::<S>(input, part, |parsed, p| match p {
+        1 => S::part1(parsed).answer(input),
+        _ => S::part2(parsed).answer(input),
+    })
+}
+
+//Like `solve`, with the parts solved by `solve_part` given the parsed input and the part
+pub(crate) fn solve_with<S: Solution>(input: &str, part: Option<u8>, solve_part: impl Fn(&S::Input, u8) -> Result<String, ParseError>) -> Result<(Answers, Elapsed), ParseError> {
+    let start = Instant::now();
+    let input = {
+        let _span = span("parse");
+        S::parse(input)?
+    };
+    let parse = start.elapsed();
+    let (part_1, elapsed_1) = timed("part1", part.unwrap_or(1) == 1, || solve_part(&input, 1))?;
+    let (part_2, elapsed_2) = timed("part2", part.unwrap_or(2) == 2, || solve_part(&input, 2))?;
+    Ok((Answers { part_1, part_2 }, Elapsed { parse, part_1: elapsed_1, part_2: elapsed_2 }))
+}
+
+//Only parses the input, for checking parsers on their own
+pub fn parse<S: Solution>(input: &str) -> Result<(), ParseError> {
+    S::parse(input).map(|_| ())
+}
+
+//Runs a stage in a span of its own if it is enabled, with the time it took
+fn timed(name: &str, enabled: bool, f: impl FnOnce() -> Result<String, ParseError>) -> Result<(Option<String>, Option<Duration>), ParseError> {
+    if !enabled {
+        return Ok((None, None));
+    }
+    let _span = span(name);
+    let start = Instant::now();
+    let answer = f()?;
+    Ok((Some(answer), Some(start.elapsed())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day18::Day18;
+    use crate::input::{self, InputSource};
+    use super::{solve, Answers};
+
+    #[test]
+    fn solve_only_runs_requested_part() {
+        let example = input::load(2022, 18, &InputSource::Variant("test".to_string())).unwrap();
+        let (both, _) = solve::<Day18>(&example, None).unwrap();
+        assert_eq!(both, Answers { part_1: Some("64".to_string()), part_2: Some("58".to_string()) });
+
+        let (second, elapsed) = solve::<Day18>(&example, Some(2)).unwrap();
+        assert_eq!(second, Answers { part_1: None, part_2: Some("58".to_string()) });
+        assert!(elapsed.part_1.is_none() && elapsed.part_2.is_some());
+    }
+}
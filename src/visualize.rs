@@ -0,0 +1,225 @@
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::{ParseError, Solution};
+
+//A picture of a simulation at one of its steps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub caption: String,
+    pub picture: String,
+}
+
+impl Frame {
+    pub fn new(caption: impl Into<String>, picture: String) -> Self {
+        Frame { caption: caption.into(), picture }
+    }
+}
+
+//Something that watches the frames of a simulation
+pub trait Viewer {
+    //Shows a frame, returning whether to keep watching
+    fn show(&mut self, frame: Frame) -> bool;
+}
+
+impl Viewer for Vec<Frame> {
+    fn show(&mut self, frame: Frame) -> bool {
+        self.push(frame);
+        true
+    }
+}
+
+//Where a simulation sends a frame after every step; frames are only rendered while a viewer is watching
+pub struct Recorder<'a> {
+    viewer: Option<&'a mut dyn Viewer>,
+    every: usize,
+    steps: usize,
+}
+
+impl<'a> Recorder<'a> {
+    //Records nothing, for solving without watching
+    pub fn off() -> Self {
+        Recorder { viewer: None, every: 1, steps: 0 }
+    }
+
+    //Shows every `every`th step to `viewer`
+    pub fn new(viewer: &'a mut dyn Viewer, every: usize) -> Self {
+        Recorder { viewer: Some(viewer), every: every.max(1), steps: 0 }
+    }
+
+    pub fn frame(&mut self, render: impl FnOnce() -> Frame) {
+        let Some(viewer) = self.viewer.as_mut() else { return };
+        let due = self.steps.is_multiple_of(self.every);
+        self.steps += 1;
+        if due && !viewer.show(render()) {
+            self.viewer = None;
+        }
+    }
+}
+
+//A solution with a simulation behind its parts that can be watched step by step
+pub trait Visualize: Solution {
+    fn visualize(input: &Self::Input, part: u8, recorder: &mut Recorder);
+}
+
+//Parses the input and runs the simulation of a part, sending its frames to `recorder`
+pub fn record<S: Visualize>(input: &str, part: u8, recorder: &mut Recorder) -> Result<(), ParseError> {
+    let input = S::parse(input)?;
+    S::visualize(&input, part, recorder);
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Pause,
+    Step,
+    Quit,
+}
+
+//Plays frames in the terminal at a fixed rate, redrawing the screen for every frame
+pub struct Player {
+    delay: Duration,
+    paused: bool,
+    controls: Option<Receiver<Control>>,
+    shown: usize,
+    quit: bool,
+}
+
+impl Player {
+    //With `controls`, lines typed on stdin pause and resume (empty line), step (s) and stop watching (q)
+    pub fn new(fps: f64, paused: bool, controls: bool) -> Self {
+        let controls = controls.then(|| {
+            let (sender, receiver) = mpsc::channel();
+            thread::spawn(move || {
+                for line in io::stdin().lines() {
+                    let Ok(line) = line else { break };
+                    let control = match line.trim() {
+                        "" | "p" => Control::Pause,
+                        "s" => Control::Step,
+                        "q" => Control::Quit,
+                        _ => continue,
+                    };
+                    if sender.send(control).is_err() {
+                        break;
+                    }
+                }
+            });
+            receiver
+        });
+        Player { delay: Duration::from_secs_f64(1.0 / fps), paused: paused && controls.is_some(), controls, shown: 0, quit: false }
+    }
+
+    //Waits until the next frame is due, returning whether to keep watching
+    fn wait(&mut self) -> bool {
+        let Some(controls) = &self.controls else {
+            thread::sleep(self.delay);
+            return true;
+        };
+        loop {
+            let control = match self.paused {
+                true => controls.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                false => controls.recv_timeout(self.delay),
+            };
+            match control {
+                Ok(Control::Pause) if !self.paused => self.paused = true,
+                Ok(Control::Pause) => {
+                    self.paused = false;
+                    return true;
+                }
+                Ok(Control::Step) => {
+                    self.paused = true;
+                    return true;
+                }
+                Ok(Control::Quit) => return false,
+                Err(RecvTimeoutError::Timeout) => return true,
+                Err(RecvTimeoutError::Disconnected) => break, //stdin was closed, keep playing without controls
+            }
+        }
+        self.controls = None;
+        self.paused = false;
+        true
+    }
+}
+
+impl Viewer for Player {
+    fn show(&mut self, frame: Frame) -> bool {
+        if self.quit {
+            return false;
+        }
+        self.shown += 1;
+        let help = match (&self.controls, self.paused) {
+            (None, _) => "",
+            (Some(_), false) => "[enter] pause  [q] stop",
+            (Some(_), true) => "[enter] play  [s] step  [q] stop",
+        };
+        let mut out = io::stdout().lock();
+        //Clear the screen and draw from its top left corner
+        let drawn = write!(out, "\x1b[H\x1b[2J{}\n{}\n\nframe {}  {}\n", frame.caption, frame.picture, self.shown, help)
+            .and_then(|_| out.flush());
+        drop(out);
+        self.quit = drawn.is_err() || !self.wait();
+        !self.quit
+    }
+}
+
+//Writes frames one after the other to a text file, for reviewing them offline
+pub struct Dump<W: Write> {
+    out: W,
+    written: usize,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> Dump<W> {
+    pub fn new(out: W) -> Self {
+        Dump { out, written: 0, error: None }
+    }
+
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    //Flushes the frames, reporting the first write that failed
+    pub fn finish(mut self) -> io::Result<()> {
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => self.out.flush(),
+        }
+    }
+}
+
+impl<W: Write> Viewer for Dump<W> {
+    fn show(&mut self, frame: Frame) -> bool {
+        self.written += 1;
+        match writeln!(self.out, "=== frame {}: {} ===\n{}\n", self.written, frame.caption, frame.picture) {
+            Ok(()) => true,
+            Err(e) => {
+                self.error = Some(e);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day14::Day14;
+    use crate::input::{self, InputSource};
+    use super::{record, Dump, Frame, Recorder};
+
+    #[test]
+    fn recorders_show_every_nth_step() {
+        let example = input::load(2022, 14, &InputSource::Variant("test".to_string())).unwrap();
+        let mut frames: Vec<Frame> = vec![];
+        record::<Day14>(&example, 1, &mut Recorder::new(&mut frames, 1)).unwrap();
+        assert_eq!(frames.len(), 24);
+        assert_eq!(frames[23].picture.matches('o').count(), 24);
+
+        let mut dump = Dump::new(vec![]);
+        record::<Day14>(&example, 1, &mut Recorder::new(&mut dump, 10)).unwrap();
+        assert_eq!(dump.written(), 3);
+        let text = String::from_utf8(dump.out).unwrap();
+        assert!(text.starts_with("=== frame 1: 1 unit of sand ===\n"));
+    }
+}